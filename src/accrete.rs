@@ -3,8 +3,15 @@ use crate::consts::*;
 use crate::events_log::accrete_event::AccreteEvents;
 #[cfg(events_log)]
 use crate::events_log::event_source::EventSource;
+use crate::habitable_zone::HabitableZoneModel;
+use crate::lagrange::trojan_swarms;
+use crate::solar_system::SolReport;
+use crate::structs::disk::Disk;
+use crate::structs::pinned::PinnedPlanet;
 use crate::structs::planetesimal::Planetesimal;
+use crate::structs::primary_star::PrimaryStar;
 use crate::structs::system::System;
+use crate::structure::Structure;
 use crate::utils::*;
 
 use rand::{Rng, SeedableRng};
@@ -28,7 +35,7 @@ use serde::{Deserialize, Serialize};
 /// **b** - Crit_mass coeff is used as threshold for planet to become gas giant. Recommended range: 1.0e-5 - 1.2e-5
 /// *Default: 1.2e-5*
 ///
-/// **post_accretion_intensity** - Amount of random planetesimals that will bomb planets of created system after accretion.
+/// **post_accretion_intensity** - Number of impactors that bomb planets after accretion. Mass they deliver is the part of the scattered disk that reaches the inner system, intensity only splits it into impactors of equal mass.
 /// *Default: 1000*
 ///
 /// Parameters specific for standalone planet generation
@@ -44,6 +51,33 @@ use serde::{Deserialize, Serialize};
 /// **stellar_luminosity** - Primary star luminosity.
 /// *Default: 1.0*
 ///
+/// **system_age** - Age of the system in years, limited by main sequence lifetime of the star. Sets stellar activity, radiogenic heating and tidal despinning of planets.
+/// *Default: 4.57e9 (age of the Solar System)*
+///
+/// **stellar_wind** - Multiplier of stellar wind dynamic pressure, which follows X-ray activity of the star. Sets magnetopause distance and atmospheric stripping of planets without strong magnetic field.
+/// *Default: 1.0*
+///
+/// **hydrodynamic_escape** - Evaporate hydrogen and helium envelopes of planets by XUV heating of young star, in addition to thermal escape of each gas.
+/// *Default: true*
+///
+/// **regular_moons** - Form regular moon systems of gas giants by accretion in circumplanetary disks, in addition to captured moons.
+/// *Default: false*
+///
+/// **trojans** - Generate Trojan swarms at L4 and L5 points of planets heavier than 10 Earth masses.
+/// *Default: false*
+///
+/// **disk** - Protoplanetary disk model, see structs::disk for built-in profiles.
+/// *Default: Dole's exponential cloud*
+///
+/// **structure** - Mass-radius relation used for planetary radius and density, see structure module for built-in models.
+/// *Default: Kothari radius and empirical gas giant density*
+///
+/// **habitable_zone** - Ecosphere used for planetary temperatures, Kopparapu limits by stellar temperature or legacy flux factors per spectral class.
+/// *Default: Kopparapu runaway to maximum greenhouse limits*
+///
+/// **pinned_planets** - Planets placed at given orbits and masses before accretion, with their dust lanes swept. Protected ones are kept as given and bodies crossing their orbits are scattered away, ones that can grow merge, capture moons or get captured like other planets. Pins failing PinnedPlanet::validate are skipped by planetary_system, try_planetary_system returns the error instead.
+/// *Default: []*
+///
 /// **events_log** - AccreteEvents log.
 /// *Default: []*
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,6 +92,15 @@ pub struct Accrete {
     pub planet_e: f64,
     pub planet_mass: f64,
     pub stellar_luminosity: f64,
+    pub stellar_wind: f64,
+    pub system_age: f64,
+    pub hydrodynamic_escape: bool,
+    pub regular_moons: bool,
+    pub trojans: bool,
+    pub disk: Disk,
+    pub structure: Structure,
+    pub habitable_zone: HabitableZoneModel,
+    pub pinned_planets: Vec<PinnedPlanet>,
     #[cfg(events_log)]
     pub events_log: AccreteEvents,
     pub rng: ChaCha8Rng,
@@ -80,9 +123,18 @@ impl Default for Accrete {
             b: B,
             post_accretion_intensity: 1000,
             stellar_luminosity: 1.0,
+            stellar_wind: 1.0,
+            system_age: SOLAR_AGE,
+            hydrodynamic_escape: true,
+            regular_moons: false,
+            trojans: false,
             planet_a,
             planet_e,
             planet_mass,
+            disk: Disk::default(),
+            structure: Structure::default(),
+            habitable_zone: HabitableZoneModel::default(),
+            pinned_planets: Vec::new(),
             rng,
             #[cfg(events_log)]
             events_log: vec![],
@@ -107,9 +159,18 @@ impl Accrete {
             b: B,
             post_accretion_intensity: 1000,
             stellar_luminosity: 1.0,
+            stellar_wind: 1.0,
+            system_age: SOLAR_AGE,
+            hydrodynamic_escape: true,
+            regular_moons: false,
+            trojans: false,
             planet_a,
             planet_e,
             planet_mass,
+            disk: Disk::default(),
+            structure: Structure::default(),
+            habitable_zone: HabitableZoneModel::default(),
+            pinned_planets: Vec::new(),
             rng,
             #[cfg(events_log)]
             events_log: vec![],
@@ -126,6 +187,15 @@ impl Accrete {
             cloud_eccentricity,
             b,
             post_accretion_intensity,
+            stellar_wind,
+            system_age,
+            hydrodynamic_escape,
+            regular_moons,
+            trojans,
+            disk,
+            structure,
+            habitable_zone,
+            pinned_planets,
             rng,
 
             events_log,
@@ -139,6 +209,15 @@ impl Accrete {
             cloud_eccentricity,
             b,
             post_accretion_intensity,
+            stellar_wind,
+            system_age,
+            hydrodynamic_escape,
+            regular_moons,
+            trojans,
+            disk,
+            structure,
+            habitable_zone,
+            pinned_planets,
             rng,
             ..
         } = self;
@@ -149,14 +228,43 @@ impl Accrete {
             *k,
             *cloud_eccentricity,
             *b,
+            disk.clone(),
+            *system_age,
         );
+        planetary_system.primary_star.stellar_wind *= *stellar_wind;
+        let PrimaryStar {
+            stellar_luminosity,
+            spectral_class,
+            habitable_zone: limits,
+            ..
+        } = planetary_system.primary_star;
+        planetary_system.primary_star.ecosphere =
+            habitable_zone.ecosphere(&limits, &stellar_luminosity, &spectral_class);
 
         #[cfg(events_log)]
         planetary_system.event("system_setup", events_log);
 
-        planetary_system.distribute_planetary_masses(rng, #[cfg(events_log)]events_log);
+        planetary_system.seed_pinned_planets(
+            pinned_planets,
+            rng,
+            #[cfg(events_log)]
+            events_log,
+        );
+        planetary_system.distribute_planetary_masses(
+            rng,
+            #[cfg(events_log)]
+            events_log,
+        );
+        planetary_system.form_cometary_reservoir(rng);
         planetary_system.post_accretion(*post_accretion_intensity, rng, #[cfg(events_log)]events_log);
-        planetary_system.process_planets(rng);
+        if *regular_moons {
+            planetary_system.form_regular_moons(rng);
+        }
+        planetary_system.process_planets(hydrodynamic_escape, structure, rng);
+        if *trojans {
+            planetary_system.form_trojan_swarms(rng);
+        }
+        planetary_system.form_asteroid_belts();
 
         #[cfg(events_log)]
         planetary_system.event("planetary_environment_generated", events_log);
@@ -167,6 +275,16 @@ impl Accrete {
         planetary_system
     }
 
+    /// Generate planetary system, failing on the first pinned planet rejected by PinnedPlanet::validate instead of skipping it.
+    pub fn try_planetary_system(&mut self) -> Result<System, String> {
+        for (i, pinned) in self.pinned_planets.iter().enumerate() {
+            pinned
+                .validate()
+                .map_err(|err| format!("{} (pinned planet {})", err, i))?;
+        }
+        Ok(self.planetary_system())
+    }
+
 
     /// Generate planet.
     pub fn planet(&mut self) -> Planetesimal {
@@ -178,6 +296,14 @@ impl Accrete {
             planet_e,
             planet_mass,
             post_accretion_intensity,
+            dust_density_coeff,
+            stellar_wind,
+            system_age,
+            hydrodynamic_escape,
+            trojans,
+            structure,
+            habitable_zone,
+            disk,
             rng,
             events_log,
             ..
@@ -190,21 +316,50 @@ impl Accrete {
             planet_e,
             planet_mass,
             post_accretion_intensity,
+            dust_density_coeff,
+            stellar_wind,
+            system_age,
+            hydrodynamic_escape,
+            trojans,
+            structure,
+            habitable_zone,
+            disk,
             rng,
             ..
         } = self;
 
-        Planetesimal::random_planet(
+        let mut planet = Planetesimal::random_planet(
             *stellar_luminosity,
             *stellar_mass,
             *planet_a,
             *planet_e,
             *planet_mass,
             *post_accretion_intensity,
+            *dust_density_coeff,
+            *stellar_wind,
+            *system_age,
+            *hydrodynamic_escape,
+            habitable_zone,
+            structure,
+            disk,
             rng,
             #[cfg(events_log)]
             events_log,
-        )
+        );
+        if *trojans {
+            planet.trojans = trojan_swarms(&planet, stellar_mass, rng);
+        }
+        planet
     }
 
+    /// Derive environments of the Solar System preset with current structure and escape settings, compared against measured values.
+    pub fn sol_report(&mut self) -> SolReport {
+        let Accrete {
+            hydrodynamic_escape,
+            structure,
+            rng,
+            ..
+        } = self;
+        SolReport::new(hydrodynamic_escape, structure, rng)
+    }
 }
@@ -0,0 +1,319 @@
+use crate::consts::*;
+use crate::structs::primary_star::stellar_radius_au;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Convective turnover time (days) from stellar mass, valid for 0.09-1.36 solar masses.
+/// [Wright et al. 2011, The stellar-activity-rotation relationship and the evolution of stellar dynamos](https://doi.org/10.1088/0004-637X/743/1/48)
+const TURNOVER_MASS_RANGE: (f64, f64) = (0.09, 1.36);
+
+/// X-ray to bolometric luminosity of saturated stars, which holds below critical Rossby number, and its fall with Rossby number above it.
+const SATURATED_XRAY_RATIO: f64 = 7.41e-4;
+const SATURATION_ROSSBY_NUMBER: f64 = 0.13;
+const XRAY_ROSSBY_INDEX: f64 = -2.7;
+
+/// Spin-down of cool stars from fast (C) to slow (I) sequence, constants in days/Myr and Myr/day, initial period in days.
+/// Turnover times of Barnes & Kim are local to the base of convective zone, 34 days for the Sun against 14.5 days of global ones by Wright.
+/// [Barnes & Kim 2010, Angular momentum loss from cool stars: an empirical expression and connection to stellar activity](https://doi.org/10.1088/0004-637X/721/1/675)
+const SPIN_DOWN_K_C: f64 = 0.646;
+const SPIN_DOWN_K_I: f64 = 452.0;
+const INITIAL_ROTATION_PERIOD: f64 = 1.1;
+const LOCAL_TURNOVER_RATIO: f64 = 2.35;
+
+/// EUV (100-920 Å) from X-ray luminosity, log L_EUV = 0.86 log L_X + 4.8 in erg/s.
+/// [Sanz-Forcada et al. 2011, Estimation of the XUV radiation onto close planets and their evaporation](https://doi.org/10.1051/0004-6361/201116594)
+const EUV_XRAY_INDEX: f64 = 0.86;
+const EUV_XRAY_OFFSET: f64 = 4.8;
+
+/// Flare energies (erg) follow power law dN/dE ∝ E^-2 between limits, flares release energy equal to X-ray luminosity.
+/// [Hawley et al. 2014, Kepler flares. I. Active and inactive M dwarfs](https://doi.org/10.1088/0004-637X/797/2/121)
+const FLARE_ENERGY_RANGE: (f64, f64) = (1.0e30, 1.0e36);
+/// Energy of flares counted in flare rate, erg.
+const REFERENCE_FLARE_ENERGY: f64 = 1.0e32;
+
+/// Mass loss per stellar surface area grows with X-ray surface flux as F_X^1.34 up to 10^6 erg/cm2/s, where winds of most active stars weaken.
+/// [Wood et al. 2005, New mass-loss measurements from astrospheric Lyα absorption](https://doi.org/10.1086/432716)
+const WIND_XRAY_INDEX: f64 = 1.34;
+const WIND_XRAY_FLUX_LIMIT: f64 = 1.0e6;
+
+/// Time steps of activity history, spaced geometrically from first million years to system age.
+const TIME_STEPS: usize = 100;
+const FIRST_STEP_YEARS: f64 = 1.0e6;
+
+/// Magnetic activity of the star at system age and its high-energy output over time.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StellarActivity {
+    // years, main sequence age capped by age of universe
+    pub age: f64,
+    // units of erg/s
+    pub bolometric_luminosity: f64,
+    // global convective turnover time, days
+    pub convective_turnover: f64,
+    // units of days
+    pub rotation_period: f64,
+    // rotation period over convective turnover time
+    pub rossby_number: f64,
+    // X-ray luminosity saturated at fixed share of bolometric
+    pub is_saturated: bool,
+    // age when star spins down out of saturated activity, years
+    pub saturation_age: f64,
+    // 5-100 Å, erg/s
+    pub xray_luminosity: f64,
+    // 100-920 Å, erg/s
+    pub euv_luminosity: f64,
+    // flares above 10^32 erg per day
+    pub flare_rate: f64,
+    // stellar wind dynamic pressure at 1 AU relative to the present Sun
+    pub wind_pressure: f64,
+}
+
+impl StellarActivity {
+    /// Activity of a star spinning down by magnetic braking over system age in years. Faster rotation and deeper convection of low mass stars keep them saturated for longer.
+    pub fn new(stellar_mass: &f64, stellar_luminosity: &f64, age: &f64) -> Self {
+        let age = age.min(UNIVERSE_AGE);
+        let bolometric_luminosity = stellar_luminosity * ERG_PER_SEC_PER_SOLAR_LUMINOSITY;
+        let convective_turnover = convective_turnover_time(stellar_mass);
+        let rotation_period = rotation_period(&convective_turnover, &age);
+        let rossby_number = rotation_period / convective_turnover;
+        let saturation_age = spin_down_age(
+            &convective_turnover,
+            &(SATURATION_ROSSBY_NUMBER * convective_turnover),
+        );
+        let xray_luminosity = xray_to_bolometric(&rossby_number) * bolometric_luminosity;
+        let xray_surface_flux =
+            xray_luminosity / (4.0 * PI * (stellar_radius_au(*stellar_mass) * CM_PER_AU).powf(2.0));
+
+        Self {
+            age,
+            bolometric_luminosity,
+            convective_turnover: float_to_precision(convective_turnover),
+            rotation_period: float_to_precision(rotation_period),
+            rossby_number: float_to_precision(rossby_number),
+            is_saturated: rossby_number <= SATURATION_ROSSBY_NUMBER,
+            saturation_age: float_to_precision(saturation_age),
+            xray_luminosity,
+            euv_luminosity: euv_luminosity(&xray_luminosity),
+            flare_rate: float_to_precision(flare_rate(&xray_luminosity, &REFERENCE_FLARE_ENERGY)),
+            wind_pressure: float_to_precision(
+                stellar_radius_au(*stellar_mass).powf(2.0) / stellar_radius_au(1.0).powf(2.0)
+                    * (xray_surface_flux.min(WIND_XRAY_FLUX_LIMIT) / solar_xray_surface_flux())
+                        .powf(WIND_XRAY_INDEX),
+            ),
+        }
+    }
+
+    /// X-ray and EUV luminosity (erg/s) at age in years.
+    pub fn xuv_luminosity(&self, age: &f64) -> f64 {
+        let rossby_number =
+            rotation_period(&self.convective_turnover, age) / self.convective_turnover;
+        let xray_luminosity = xray_to_bolometric(&rossby_number) * self.bolometric_luminosity;
+        xray_luminosity + euv_luminosity(&xray_luminosity)
+    }
+
+    /// XUV flux (erg/cm2/s) at distance in AU and age in years.
+    pub fn xuv_flux(&self, distance: &f64, age: &f64) -> f64 {
+        self.xuv_luminosity(age) / (4.0 * PI * (distance * CM_PER_AU).powf(2.0))
+    }
+
+    /// Flares per day releasing more than energy in erg at system age.
+    pub fn flares_above(&self, energy: &f64) -> f64 {
+        flare_rate(&self.xray_luminosity, energy)
+    }
+
+    /// Middle of each time step and its length in years, first step covers saturated youth from formation. Newborn star has no history.
+    pub fn history(&self) -> Vec<(f64, f64)> {
+        if self.age <= 0.0 {
+            return Vec::new();
+        }
+        let first = FIRST_STEP_YEARS.min(self.age);
+        let ratio = (self.age / first).powf(1.0 / TIME_STEPS as f64);
+        let mut steps = vec![(first / 2.0, first)];
+        let mut start = first;
+        for _ in 0..TIME_STEPS {
+            let end = start * ratio;
+            steps.push(((start * end).sqrt(), end - start));
+            start = end;
+        }
+        steps
+    }
+
+    /// XUV fluence (erg/cm2) at distance in AU over system age.
+    pub fn xuv_fluence(&self, distance: &f64) -> f64 {
+        self.history()
+            .iter()
+            .map(|(time, duration)| self.xuv_flux(distance, time) * duration * SECONDS_PER_YEAR)
+            .sum()
+    }
+
+    /// The Sun at the age of Earth.
+    pub fn solar() -> Self {
+        Self::new(&1.0, &1.0, &EARTH_AGE)
+    }
+}
+
+/// Convective turnover time in days, log τ = 1.16 - 1.49 log M - 0.54 log² M.
+pub fn convective_turnover_time(stellar_mass: &f64) -> f64 {
+    let log_mass = stellar_mass
+        .clamp(TURNOVER_MASS_RANGE.0, TURNOVER_MASS_RANGE.1)
+        .log10();
+    10.0_f64.powf(1.16 - 1.49 * log_mass - 0.54 * log_mass.powf(2.0))
+}
+
+/// Age in years at which star with global convective turnover time in days spins down to rotation period in days.
+pub fn spin_down_age(convective_turnover: &f64, rotation_period: &f64) -> f64 {
+    let turnover = LOCAL_TURNOVER_RATIO * convective_turnover;
+    let period = rotation_period.max(INITIAL_ROTATION_PERIOD);
+    let myr = turnover / SPIN_DOWN_K_C * (period / INITIAL_ROTATION_PERIOD).ln()
+        + SPIN_DOWN_K_I / (2.0 * turnover) * (period.powf(2.0) - INITIAL_ROTATION_PERIOD.powf(2.0));
+    myr * 1.0e6
+}
+
+/// Rotation period in days at age in years, found by Newton steps on spin-down age kept inside bisection bracket.
+pub fn rotation_period(convective_turnover: &f64, age: &f64) -> f64 {
+    let turnover = LOCAL_TURNOVER_RATIO * convective_turnover;
+    // slow sequence alone spins star down faster than both sequences together
+    let mut low = INITIAL_ROTATION_PERIOD;
+    let mut high = (2.0 * turnover * age.max(0.0) / 1.0e6 / SPIN_DOWN_K_I
+        + INITIAL_ROTATION_PERIOD.powf(2.0))
+    .sqrt();
+    let mut period = high;
+    for _ in 0..60 {
+        let excess = spin_down_age(convective_turnover, &period) - age;
+        if excess.abs() < 1.0 {
+            break;
+        }
+        match excess > 0.0 {
+            true => high = period,
+            false => low = period,
+        }
+        let slope = (turnover / SPIN_DOWN_K_C / period + SPIN_DOWN_K_I / turnover * period) * 1.0e6;
+        let next = period - excess / slope;
+        period = match next > low && next < high {
+            true => next,
+            false => (low + high) / 2.0,
+        };
+    }
+    period
+}
+
+/// Share of bolometric luminosity emitted in X-rays by star with Rossby number.
+pub fn xray_to_bolometric(rossby_number: &f64) -> f64 {
+    match *rossby_number <= SATURATION_ROSSBY_NUMBER {
+        true => SATURATED_XRAY_RATIO,
+        false => {
+            SATURATED_XRAY_RATIO
+                * (rossby_number / SATURATION_ROSSBY_NUMBER).powf(XRAY_ROSSBY_INDEX)
+        }
+    }
+}
+
+/// EUV luminosity in erg/s from X-ray luminosity in erg/s.
+pub fn euv_luminosity(xray_luminosity: &f64) -> f64 {
+    if *xray_luminosity <= 0.0 {
+        return 0.0;
+    }
+    10.0_f64.powf(EUV_XRAY_INDEX * xray_luminosity.log10() + EUV_XRAY_OFFSET)
+}
+
+/// Flares per day above energy in erg of star with X-ray luminosity in erg/s.
+pub fn flare_rate(xray_luminosity: &f64, energy: &f64) -> f64 {
+    let (min_energy, max_energy) = FLARE_ENERGY_RANGE;
+    if *energy >= max_energy {
+        return 0.0;
+    }
+    let daily_energy = xray_luminosity * SECONDS_PER_HOUR * 24.0;
+    daily_energy / (max_energy / min_energy).ln()
+        * (1.0 / energy.max(min_energy) - 1.0 / max_energy)
+}
+
+/// X-ray surface flux of the Sun at the age of Earth, erg/cm2/s.
+fn solar_xray_surface_flux() -> f64 {
+    let rossby_number = rotation_period(&convective_turnover_time(&1.0), &EARTH_AGE)
+        / convective_turnover_time(&1.0);
+    xray_to_bolometric(&rossby_number) * ERG_PER_SEC_PER_SOLAR_LUMINOSITY
+        / (4.0 * PI * (stellar_radius_au(1.0) * CM_PER_AU).powf(2.0))
+}
+
+/// High-energy radiation received by a body.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct XuvExposure {
+    // at system age, erg/cm2/s
+    pub xuv_flux: f64,
+    // integrated over system age, erg/cm2
+    pub xuv_dose: f64,
+    // dose relative to Earth's
+    pub relative_dose: f64,
+    // flares per year at least as strong at the body as great flare of AD Leo at its habitable zone
+    pub ozone_depleting_flares: f64,
+}
+
+/// Great flare of AD Leo (erg) which removed most of ozone of Earth-like planet at 0.16 AU.
+/// [Segura et al. 2010, The effect of a strong stellar flare on the atmospheric chemistry of an Earth-like planet orbiting an M dwarf](https://doi.org/10.1089/ast.2009.0376)
+const GREAT_FLARE_ENERGY: f64 = 1.0e34;
+const GREAT_FLARE_DISTANCE: f64 = 0.16;
+
+impl XuvExposure {
+    /// Exposure at distance in AU from active star.
+    pub fn new(distance: &f64, activity: &StellarActivity) -> Self {
+        if *distance <= 0.0 {
+            return Self::default();
+        }
+        let xuv_dose = activity.xuv_fluence(distance);
+        let earth_dose = StellarActivity::solar().xuv_fluence(&1.0);
+        let ozone_depleting_energy =
+            GREAT_FLARE_ENERGY * (distance / GREAT_FLARE_DISTANCE).powf(2.0);
+
+        Self {
+            xuv_flux: float_to_precision(activity.xuv_flux(distance, &activity.age)),
+            xuv_dose,
+            relative_dose: float_to_precision(xuv_dose / earth_dose),
+            ozone_depleting_flares: float_to_precision(
+                activity.flares_above(&ozone_depleting_energy) * DAYS_IN_A_YEAR,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solar_activity() {
+        let sun = StellarActivity::solar();
+        assert!(sun.rotation_period > 24.0 && sun.rotation_period < 27.0);
+        assert!(sun.xray_luminosity > 1.0e27 && sun.xray_luminosity < 5.0e27);
+        assert!(!sun.is_saturated && sun.saturation_age < 1.0e8);
+        assert!((sun.wind_pressure - 1.0).abs() < 1.0e-6);
+        let earth = XuvExposure::new(&1.0, &sun);
+        assert!((earth.relative_dose - 1.0).abs() < 1.0e-6);
+        assert!(earth.ozone_depleting_flares < 0.1);
+    }
+
+    #[test]
+    fn newborn_star_has_no_dose() {
+        let star = StellarActivity::new(&1.0, &1.0, &0.0);
+        assert!(star.history().is_empty());
+        let planet = XuvExposure::new(&1.0, &star);
+        assert_eq!(planet.xuv_dose, 0.0);
+        assert!(planet.xuv_flux.is_finite() && planet.xuv_flux > 0.0);
+    }
+
+    #[test]
+    fn red_dwarf_stays_active() {
+        let sun = StellarActivity::solar();
+        let red_dwarf = StellarActivity::new(&0.3, &0.0148, &EARTH_AGE);
+        assert!(red_dwarf.saturation_age > 5.0 * sun.saturation_age);
+        assert!(red_dwarf.rotation_period > sun.rotation_period);
+        // Habitable zone with Earth's bolometric flux
+        let planet = XuvExposure::new(&0.0148_f64.sqrt(), &red_dwarf);
+        assert!(planet.relative_dose > 5.0);
+        // Saturated young red dwarf flares often enough to keep ozone layer depleted
+        let young = XuvExposure::new(
+            &0.0148_f64.sqrt(),
+            &StellarActivity::new(&0.3, &0.0148, &3.0e8),
+        );
+        assert!(young.ozone_depleting_flares > 1.0 && planet.ozone_depleting_flares < 1.0);
+    }
+}
@@ -0,0 +1,586 @@
+use crate::consts::*;
+use crate::structs::System;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Orbital period range of period-radius comparison, days.
+const PERIOD_RANGE: (f64, f64) = (0.1, 1.0e5);
+
+/// Planet radius range of period-radius comparison, Earth radii.
+const RADIUS_RANGE: (f64, f64) = (0.1, 30.0);
+
+/// Planet mass range of mass-semi-major axis comparison, Earth masses.
+const MASS_RANGE: (f64, f64) = (1.0e-3, 1.0e4);
+
+/// Semi-major axis range of mass-semi-major axis comparison, AU.
+const SEMI_MAJOR_AXIS_RANGE: (f64, f64) = (1.0e-2, 1.0e3);
+
+/// Log-spaced bins along each property of compared distributions.
+const DISTRIBUTION_BINS: usize = 10;
+
+/// Systems with this many planets or more share the last multiplicity bin.
+const MAX_MULTIPLICITY: usize = 8;
+
+/// Column layout of exported exoplanet catalogue.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum CatalogueFormat {
+    // Planetary Systems or Planetary Systems Composite Parameters table of NASA Exoplanet Archive
+    NasaExoplanetArchive,
+    // catalog export of The Extrasolar Planets Encyclopaedia
+    ExoplanetEu,
+}
+
+/// Column names of one format, first present column with a value is used.
+struct CatalogueColumns {
+    planet_name: &'static [&'static str],
+    host_name: &'static [&'static str],
+    orbital_period: &'static [&'static str],
+    semi_major_axis: &'static [&'static str],
+    eccentricity: &'static [&'static str],
+    mass: &'static [&'static str],
+    radius: &'static [&'static str],
+    stellar_mass: &'static [&'static str],
+    stellar_radius: &'static [&'static str],
+    stellar_temperature: &'static [&'static str],
+    distance: &'static [&'static str],
+    // rows of other parameter sets than the default one are skipped
+    default_flag: Option<&'static str>,
+    // Earth masses and Earth radii per catalogue unit
+    mass_unit: f64,
+    radius_unit: f64,
+}
+
+impl CatalogueFormat {
+    /// [NASA Exoplanet Archive, Planetary Systems columns](https://exoplanetarchive.ipac.caltech.edu/docs/API_PS_columns.html)
+    /// [Exoplanet.eu, Catalog](https://exoplanet.eu/catalog/)
+    fn columns(&self) -> CatalogueColumns {
+        match self {
+            CatalogueFormat::NasaExoplanetArchive => CatalogueColumns {
+                planet_name: &["pl_name"],
+                host_name: &["hostname"],
+                orbital_period: &["pl_orbper"],
+                semi_major_axis: &["pl_orbsmax"],
+                eccentricity: &["pl_orbeccen"],
+                mass: &["pl_bmasse", "pl_masse", "pl_msinie"],
+                radius: &["pl_rade"],
+                stellar_mass: &["st_mass"],
+                stellar_radius: &["st_rad"],
+                stellar_temperature: &["st_teff"],
+                distance: &["sy_dist"],
+                default_flag: Some("default_flag"),
+                mass_unit: 1.0,
+                radius_unit: 1.0,
+            },
+            CatalogueFormat::ExoplanetEu => CatalogueColumns {
+                planet_name: &["name"],
+                host_name: &["star_name"],
+                orbital_period: &["orbital_period"],
+                semi_major_axis: &["semi_major_axis"],
+                eccentricity: &["eccentricity"],
+                mass: &["mass", "mass_sini"],
+                radius: &["radius"],
+                stellar_mass: &["star_mass"],
+                stellar_radius: &["star_radius"],
+                stellar_temperature: &["star_teff"],
+                distance: &["star_distance"],
+                default_flag: None,
+                mass_unit: JUPITER_MASS_IN_EARTH_MASSES,
+                radius_unit: JUPITER_RADIUS_IN_EARTH_RADII,
+            },
+        }
+    }
+}
+
+/// Known or generated planet, missing catalogue values are none.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CataloguePlanet {
+    pub name: String,
+    // units of days
+    pub orbital_period_days: Option<f64>,
+    // units of AU
+    pub a: Option<f64>,
+    pub e: Option<f64>,
+    // units of Earth masses, minimum mass where true mass is unknown
+    pub mass: Option<f64>,
+    // units of Earth radii
+    pub radius: Option<f64>,
+}
+
+/// Host star with its planets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CatalogueHost {
+    pub name: String,
+    // units of solar masses
+    pub stellar_mass: Option<f64>,
+    // units of solar radii
+    pub stellar_radius: Option<f64>,
+    // units of degrees Kelvin
+    pub stellar_temperature: Option<f64>,
+    // units of parsecs
+    pub distance: Option<f64>,
+    pub planets: Vec<CataloguePlanet>,
+}
+
+/// Planets grouped by host star.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Catalogue {
+    pub hosts: Vec<CatalogueHost>,
+}
+
+impl Catalogue {
+    /// Read catalogue exported as CSV from local file.
+    pub fn from_file(path: &str, format: &CatalogueFormat) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read catalogue {}: {}", path, e))?;
+        Self::parse(&text, format)
+    }
+
+    /// Parse catalogue CSV text. Lines starting with # before the header are comments, except exoplanet.eu header itself.
+    pub fn parse(text: &str, format: &CatalogueFormat) -> Result<Self, String> {
+        let columns = format.columns();
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let header_line = match format {
+            CatalogueFormat::NasaExoplanetArchive => lines.find(|line| !line.starts_with('#')),
+            CatalogueFormat::ExoplanetEu => lines.next(),
+        }
+        .ok_or("Catalogue has no header row".to_string())?;
+        let header: Vec<String> = split_csv_line(header_line)
+            .iter()
+            .map(|column| column.trim_start_matches('#').trim().to_string())
+            .collect();
+        let positions = |names: &[&str]| -> Vec<usize> {
+            names
+                .iter()
+                .filter_map(|name| header.iter().position(|column| column == name))
+                .collect()
+        };
+        let required = |names: &[&str]| -> Result<Vec<usize>, String> {
+            let found = positions(names);
+            match found.is_empty() {
+                true => Err(format!("Catalogue has no {} column", names.join(" or "))),
+                false => Ok(found),
+            }
+        };
+        let planet_name = required(columns.planet_name)?;
+        let host_name = required(columns.host_name)?;
+        let orbital_period = positions(columns.orbital_period);
+        let semi_major_axis = positions(columns.semi_major_axis);
+        let eccentricity = positions(columns.eccentricity);
+        let mass = positions(columns.mass);
+        let radius = positions(columns.radius);
+        let stellar_mass = positions(columns.stellar_mass);
+        let stellar_radius = positions(columns.stellar_radius);
+        let stellar_temperature = positions(columns.stellar_temperature);
+        let distance = positions(columns.distance);
+        let default_flag = columns
+            .default_flag
+            .and_then(|name| header.iter().position(|column| column == name));
+
+        let mut hosts: Vec<CatalogueHost> = Vec::new();
+        let mut host_indices: HashMap<String, usize> = HashMap::new();
+        let mut planet_names: HashSet<String> = HashSet::new();
+        for line in lines {
+            let fields = split_csv_line(line);
+            let text = |candidates: &[usize]| {
+                candidates
+                    .iter()
+                    .filter_map(|i| fields.get(*i))
+                    .map(|field| field.trim())
+                    .find(|field| !field.is_empty())
+            };
+            let value = |candidates: &[usize]| {
+                candidates
+                    .iter()
+                    .filter_map(|i| fields.get(*i))
+                    .filter_map(|field| field.trim().parse::<f64>().ok())
+                    .find(|value| value.is_finite())
+            };
+            if let Some(i) = default_flag {
+                if fields.get(i).map(|flag| flag.trim()) != Some("1") {
+                    continue;
+                }
+            }
+            let (name, host) = match (text(&planet_name), text(&host_name)) {
+                (Some(name), Some(host)) => (name.to_string(), host.to_string()),
+                _ => continue,
+            };
+            if !planet_names.insert(name.clone()) {
+                continue;
+            }
+
+            let index = *host_indices.entry(host.clone()).or_insert_with(|| {
+                hosts.push(CatalogueHost {
+                    name: host,
+                    ..CatalogueHost::default()
+                });
+                hosts.len() - 1
+            });
+            let entry = &mut hosts[index];
+            entry.stellar_mass = entry.stellar_mass.or(value(&stellar_mass));
+            entry.stellar_radius = entry.stellar_radius.or(value(&stellar_radius));
+            entry.stellar_temperature = entry.stellar_temperature.or(value(&stellar_temperature));
+            entry.distance = entry.distance.or(value(&distance));
+            entry.planets.push(CataloguePlanet {
+                name,
+                orbital_period_days: value(&orbital_period),
+                a: value(&semi_major_axis),
+                e: value(&eccentricity),
+                mass: value(&mass).map(|m| m * columns.mass_unit),
+                radius: value(&radius).map(|r| r * columns.radius_unit),
+            });
+        }
+
+        Ok(Self { hosts })
+    }
+
+    /// Generated systems as catalogue records, each planet with all values known. Moons are left out.
+    pub fn from_systems(systems: &[System]) -> Self {
+        let hosts = systems
+            .iter()
+            .enumerate()
+            .map(|(i, system)| CatalogueHost {
+                name: format!("system-{}", i + 1),
+                stellar_mass: Some(system.primary_star.stellar_mass),
+                stellar_radius: Some(float_to_precision(
+                    system.primary_star.stellar_radius_au / SOLAR_RADIUS,
+                )),
+                stellar_temperature: Some(system.primary_star.stellar_surface_temp),
+                distance: None,
+                planets: system
+                    .planets
+                    .iter()
+                    .map(|planet| CataloguePlanet {
+                        name: planet.id.clone(),
+                        orbital_period_days: Some(planet.orbital_period_days),
+                        a: Some(planet.a),
+                        e: Some(planet.e),
+                        mass: Some(float_to_precision(
+                            planet.mass * EARTH_MASSES_PER_SOLAR_MASS,
+                        )),
+                        radius: Some(float_to_precision(planet.radius / EARTH_RADIUS_IN_KM)),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self { hosts }
+    }
+
+    pub fn planets(&self) -> impl Iterator<Item = &CataloguePlanet> {
+        self.hosts.iter().flat_map(|host| host.planets.iter())
+    }
+
+    /// Pairs of two known positive planet properties.
+    fn pairs(
+        &self,
+        property: fn(&CataloguePlanet) -> (Option<f64>, Option<f64>),
+    ) -> Vec<(f64, f64)> {
+        self.planets()
+            .filter_map(|planet| match property(planet) {
+                (Some(x), Some(y)) if x > 0.0 && y > 0.0 => Some((x, y)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Two planet properties of catalogue and generated planets binned on log-spaced grid.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct JointDistribution {
+    // log10 of bin edges along each property
+    pub x_edges: Vec<f64>,
+    pub y_edges: Vec<f64>,
+    // planets with both properties known
+    pub catalogue_count: usize,
+    pub generated_count: usize,
+    // share of planets in bin, indexed by x bin then y bin, values out of range fall into edge bins
+    pub catalogue_shares: Vec<Vec<f64>>,
+    pub generated_shares: Vec<Vec<f64>>,
+    // half of summed absolute differences of shares, 0 for identical and 1 for disjoint distributions
+    pub total_variation: f64,
+    // two-sample Kolmogorov–Smirnov statistic of each property alone
+    pub x_ks_statistic: f64,
+    pub y_ks_statistic: f64,
+}
+
+impl JointDistribution {
+    pub fn new(
+        catalogue: &[(f64, f64)],
+        generated: &[(f64, f64)],
+        x_range: &(f64, f64),
+        y_range: &(f64, f64),
+        bins: &usize,
+    ) -> Self {
+        let x_edges = log_edges(x_range, bins);
+        let y_edges = log_edges(y_range, bins);
+        let catalogue_shares = binned_shares(catalogue, &x_edges, &y_edges);
+        let generated_shares = binned_shares(generated, &x_edges, &y_edges);
+        let total_variation =
+            total_variation(&catalogue_shares.concat(), &generated_shares.concat());
+        let x_ks_statistic = ks_statistic(
+            &catalogue.iter().map(|(x, _)| *x).collect::<Vec<f64>>(),
+            &generated.iter().map(|(x, _)| *x).collect::<Vec<f64>>(),
+        );
+        let y_ks_statistic = ks_statistic(
+            &catalogue.iter().map(|(_, y)| *y).collect::<Vec<f64>>(),
+            &generated.iter().map(|(_, y)| *y).collect::<Vec<f64>>(),
+        );
+
+        Self {
+            x_edges,
+            y_edges,
+            catalogue_count: catalogue.len(),
+            generated_count: generated.len(),
+            catalogue_shares,
+            generated_shares,
+            total_variation: float_to_precision(total_variation),
+            x_ks_statistic: float_to_precision(x_ks_statistic),
+            y_ks_statistic: float_to_precision(y_ks_statistic),
+        }
+    }
+}
+
+/// Number of planets per system in catalogue and generated systems.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Multiplicity {
+    // share of systems with 1, 2, .. planets, last bin holds richer systems too, systems without planets are left out
+    pub catalogue_shares: Vec<f64>,
+    pub generated_shares: Vec<f64>,
+    pub catalogue_mean: f64,
+    pub generated_mean: f64,
+    pub total_variation: f64,
+}
+
+impl Multiplicity {
+    pub fn new(catalogue: &Catalogue, generated: &Catalogue) -> Self {
+        let (catalogue_shares, catalogue_mean) = multiplicity_shares(catalogue);
+        let (generated_shares, generated_mean) = multiplicity_shares(generated);
+        let total_variation = total_variation(&catalogue_shares, &generated_shares);
+
+        Self {
+            catalogue_shares,
+            generated_shares,
+            catalogue_mean: float_to_precision(catalogue_mean),
+            generated_mean: float_to_precision(generated_mean),
+            total_variation: float_to_precision(total_variation),
+        }
+    }
+}
+
+/// How close batch of generated systems comes to known exoplanets. No detection bias is applied to generated planets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CatalogueComparison {
+    // orbital period in days against radius in Earth radii
+    pub period_radius: JointDistribution,
+    // mass in Earth masses against semi-major axis in AU
+    pub mass_semi_major_axis: JointDistribution,
+    pub multiplicity: Multiplicity,
+}
+
+impl CatalogueComparison {
+    pub fn new(catalogue: &Catalogue, systems: &[System]) -> Self {
+        let generated = Catalogue::from_systems(systems);
+        let period_radius = |planet: &CataloguePlanet| (planet.orbital_period_days, planet.radius);
+        let mass_semi_major_axis = |planet: &CataloguePlanet| (planet.mass, planet.a);
+
+        Self {
+            period_radius: JointDistribution::new(
+                &catalogue.pairs(period_radius),
+                &generated.pairs(period_radius),
+                &PERIOD_RANGE,
+                &RADIUS_RANGE,
+                &DISTRIBUTION_BINS,
+            ),
+            mass_semi_major_axis: JointDistribution::new(
+                &catalogue.pairs(mass_semi_major_axis),
+                &generated.pairs(mass_semi_major_axis),
+                &MASS_RANGE,
+                &SEMI_MAJOR_AXIS_RANGE,
+                &DISTRIBUTION_BINS,
+            ),
+            multiplicity: Multiplicity::new(catalogue, &generated),
+        }
+    }
+}
+
+/// Fields of CSV line, quoted fields may hold commas and doubled quotes.
+pub fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut is_quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, is_quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', _) => is_quoted = !is_quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Two-sample Kolmogorov–Smirnov statistic, largest distance between empirical distribution functions.
+pub fn ks_statistic(first: &[f64], second: &[f64]) -> f64 {
+    if first.is_empty() || second.is_empty() {
+        return 0.0;
+    }
+    let sorted = |values: &[f64]| {
+        let mut values = values.to_vec();
+        values.sort_by(|a, b| a.total_cmp(b));
+        values
+    };
+    let (first, second) = (sorted(first), sorted(second));
+    let (mut i, mut j, mut statistic) = (0, 0, 0.0f64);
+    while i < first.len() && j < second.len() {
+        let value = first[i].min(second[j]);
+        while i < first.len() && first[i] <= value {
+            i += 1;
+        }
+        while j < second.len() && second[j] <= value {
+            j += 1;
+        }
+        statistic =
+            statistic.max((i as f64 / first.len() as f64 - j as f64 / second.len() as f64).abs());
+    }
+    statistic
+}
+
+/// Half of summed absolute differences of two sets of shares over the same bins.
+fn total_variation(first: &[f64], second: &[f64]) -> f64 {
+    first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| (a - b).abs())
+        .sum::<f64>()
+        / 2.0
+}
+
+fn log_edges(range: &(f64, f64), bins: &usize) -> Vec<f64> {
+    let (min, max) = (range.0.log10(), range.1.log10());
+    (0..=*bins)
+        .map(|i| float_to_precision(min + (max - min) * i as f64 / *bins as f64))
+        .collect()
+}
+
+fn bin_index(value: &f64, edges: &[f64]) -> usize {
+    let bins = edges.len() - 1;
+    let position = (value.log10() - edges[0]) / (edges[bins] - edges[0]) * bins as f64;
+    (position.max(0.0) as usize).min(bins - 1)
+}
+
+fn binned_shares(pairs: &[(f64, f64)], x_edges: &[f64], y_edges: &[f64]) -> Vec<Vec<f64>> {
+    let mut shares = vec![vec![0.0; y_edges.len() - 1]; x_edges.len() - 1];
+    if pairs.is_empty() {
+        return shares;
+    }
+    let share = 1.0 / pairs.len() as f64;
+    for (x, y) in pairs {
+        shares[bin_index(x, x_edges)][bin_index(y, y_edges)] += share;
+    }
+    shares
+        .into_iter()
+        .map(|row| row.into_iter().map(float_to_precision).collect())
+        .collect()
+}
+
+fn multiplicity_shares(catalogue: &Catalogue) -> (Vec<f64>, f64) {
+    let counts: Vec<usize> = catalogue
+        .hosts
+        .iter()
+        .map(|host| host.planets.len())
+        .filter(|count| *count > 0)
+        .collect();
+    let mut shares = vec![0.0; MAX_MULTIPLICITY];
+    if counts.is_empty() {
+        return (shares, 0.0);
+    }
+    let share = 1.0 / counts.len() as f64;
+    for count in counts.iter() {
+        shares[count.min(&MAX_MULTIPLICITY) - 1] += share;
+    }
+    let mean = counts.iter().sum::<usize>() as f64 / counts.len() as f64;
+    (shares.into_iter().map(float_to_precision).collect(), mean)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Accrete;
+
+    const NASA_SAMPLE: &str = "# This file was produced by the NASA Exoplanet Archive
+# COLUMN pl_name: Planet Name
+pl_name,hostname,default_flag,pl_orbper,pl_orbsmax,pl_orbeccen,pl_bmasse,pl_rade,st_mass,st_rad,st_teff,sy_dist,pl_refname
+TRAPPIST-1 b,TRAPPIST-1,1,1.510826,0.01154,0.00622,1.374,1.116,0.09,0.12,2566,12.4299,\"<a href=x>Agol et al., 2021</a>\"
+TRAPPIST-1 b,TRAPPIST-1,0,1.51087,0.0111,,0.85,1.086,0.08,0.117,2559,12.4299,Grimm et al. 2018
+TRAPPIST-1 c,TRAPPIST-1,1,2.421937,0.01580,0.00654,1.308,1.097,0.09,0.12,2566,12.4299,Agol et al. 2021
+51 Peg b,51 Peg,1,4.230785,0.0527,0.013,,,1.09,1.15,5768,15.4614,Rosenthal et al. 2021
+";
+
+    const EU_SAMPLE: &str = "# name,star_name,orbital_period,semi_major_axis,eccentricity,mass,mass_sini,radius,star_mass,star_radius,star_teff,star_distance
+51 Peg b,51 Peg,4.230785,0.0527,0.013,,0.46,,1.11,1.27,5793,15.47
+HD 209458 b,HD 209458,3.52474859,0.04707,0.0,0.682,,1.359,1.148,1.162,6092,48.37
+";
+
+    #[test]
+    fn parse_catalogue_formats() {
+        let nasa = Catalogue::parse(NASA_SAMPLE, &CatalogueFormat::NasaExoplanetArchive).unwrap();
+        assert_eq!(nasa.hosts.len(), 2);
+        let trappist = &nasa.hosts[0];
+        assert_eq!(trappist.planets.len(), 2);
+        assert_eq!(trappist.planets[0].mass, Some(1.374));
+        assert_eq!(trappist.stellar_temperature, Some(2566.0));
+        assert_eq!(nasa.hosts[1].planets[0].radius, None);
+
+        let eu = Catalogue::parse(EU_SAMPLE, &CatalogueFormat::ExoplanetEu).unwrap();
+        let peg = &eu.hosts[0].planets[0];
+        // minimum mass in Jupiter masses stands for unknown true mass
+        assert!((peg.mass.unwrap() - 0.46 * JUPITER_MASS_IN_EARTH_MASSES).abs() < 1.0e-9);
+        let hd = &eu.hosts[1].planets[0];
+        assert!((hd.radius.unwrap() - 15.23).abs() < 0.01);
+
+        assert!(Catalogue::parse(
+            "pl_name,pl_orbper\n",
+            &CatalogueFormat::NasaExoplanetArchive
+        )
+        .is_err());
+        assert!(Catalogue::from_file("./missing.csv", &CatalogueFormat::ExoplanetEu).is_err());
+        assert_eq!(
+            split_csv_line("a,\"b, \"\"c\"\"\",d"),
+            vec!["a", "b, \"c\"", "d"]
+        );
+    }
+
+    #[test]
+    fn compare_catalogue_with_generated_systems() {
+        let systems: Vec<System> = (1..=3)
+            .map(|seed| Accrete::new(seed).planetary_system())
+            .collect();
+        let generated = Catalogue::from_systems(&systems);
+        let planets = generated.planets().count();
+
+        let itself = CatalogueComparison::new(&generated, &systems);
+        assert_eq!(itself.period_radius.generated_count, planets);
+        assert_eq!(itself.period_radius.total_variation, 0.0);
+        assert_eq!(itself.mass_semi_major_axis.x_ks_statistic, 0.0);
+        assert_eq!(itself.multiplicity.total_variation, 0.0);
+
+        let nasa = Catalogue::parse(NASA_SAMPLE, &CatalogueFormat::NasaExoplanetArchive).unwrap();
+        let comparison = CatalogueComparison::new(&nasa, &systems);
+        assert_eq!(comparison.period_radius.catalogue_count, 2);
+        assert_eq!(comparison.mass_semi_major_axis.catalogue_count, 2);
+        assert!(
+            comparison.period_radius.total_variation > 0.0
+                && comparison.period_radius.total_variation <= 1.0
+        );
+        assert!((comparison.multiplicity.catalogue_mean - 1.5).abs() < 1.0e-9);
+        assert!(comparison.multiplicity.generated_mean > comparison.multiplicity.catalogue_mean);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::consts::*;
+use crate::habitable_zone::*;
+use crate::seasons::{insolation, orbital_position};
+use crate::structs::Planetesimal;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Width of latitude bands, degrees.
+const BAND_WIDTH: f64 = 10.0;
+
+/// Orbital phases averaged for annual insolation.
+const ANNUAL_STEPS: usize = 36;
+
+/// Growth of outgoing longwave radiation with surface temperature, W/m2/K.
+/// [Budyko 1969, The effect of solar radiation variations on the climate of the Earth](https://doi.org/10.3402/tellusa.v21i5.10109)
+const OLR_SLOPE: f64 = 1.45;
+
+/// Meridional heat transport coefficient of Earth, W/m2/K, scales with surface pressure.
+const EARTH_HEAT_TRANSPORT: f64 = 3.8;
+
+/// Albedo of band covered by ice and snow in energy balance model, lower than albedo of fresh ice as it averages over clouds and ice-free patches.
+/// [North, Cahalan & Coakley 1981, Energy balance climate models](https://doi.org/10.1029/RG019i001p00091)
+const EBM_ICE_ALBEDO: f64 = 0.62;
+
+/// Albedo of open ocean, lowest one of ice-free band.
+const OPEN_SURFACE_MIN_ALBEDO: f64 = 0.06;
+
+/// Annual mean temperature of tropical band, Kelvin.
+const TROPICAL_TEMP: f64 = 291.15;
+
+/// Limit of thermal radiation of moist atmosphere, absorbed flux above it evaporates oceans.
+/// [Nakajima, Hayashi & Abe 1992, A study on the runaway greenhouse effect](https://doi.org/10.1175/1520-0469(1992)049%3C2256:ASOTRG%3E2.0.CO;2)
+const RUNAWAY_GREENHOUSE_FLUX: f64 = 282.0;
+
+/// Iterations of energy balance before it is accepted as settled.
+const MAX_ITERATIONS: usize = 500;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ClimateZone {
+    Tropical,
+    Temperate,
+    Polar,
+}
+
+/// Global state of planet's climate by stellar flux it receives, for planets with water.
+/// [Kopparapu et al. 2013, Habitable zones around main-sequence stars: new estimates](https://doi.org/10.1088/0004-637X/765/2/131)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ClimateState {
+    // liquid water at surface is stable
+    Temperate,
+    // water vapor reaches stratosphere and oceans are slowly lost to space
+    MoistGreenhouse,
+    // oceans evaporate, volatiles stay in the atmosphere
+    RunawayGreenhouse,
+    // beyond the reach of CO2 warming, only thick greenhouse atmosphere keeps water liquid
+    MaximumGreenhouse,
+    Frozen,
+    // gas giants without solid or liquid surface
+    NoSurface,
+}
+
+/// Annual mean state of one latitude band.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatitudeBand {
+    // center of band, degrees, positive to the north
+    pub latitude: f64,
+    // annual mean insolation, W/m2
+    pub insolation: f64,
+    pub albedo: f64,
+    pub temp_kelvin: f64,
+    pub ice: bool,
+    pub zone: ClimateZone,
+}
+
+/// Latitudinal climate of a planet from one-dimensional energy balance model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Climate {
+    // from south pole to north pole, empty for bodies without surface temperature
+    pub bands: Vec<LatitudeBand>,
+    // equatorward edge of ice caps, degrees of latitude, 90.0 without ice cap
+    pub ice_line_north: f64,
+    pub ice_line_south: f64,
+    pub global_temp_kelvin: f64,
+    // ice reaches the equator
+    pub is_snowball: bool,
+    // absorbed flux exceeds what moist atmosphere can radiate away
+    pub is_runaway_greenhouse: bool,
+}
+
+impl Climate {
+    /// Budyko-Sellers balance of absorbed stellar flux, outgoing radiation and heat transport towards global mean for each band.
+    /// Outgoing radiation is calibrated so that uniform planet albedo returns global surface temperature with its greenhouse rise, transport grows with surface pressure.
+    /// Bands with annual mean below freezing freeze over when planet has water. Ice-free bands darken to keep the planet's global albedo, ice caps larger than the albedo allows cool the planet and can spread to the equator.
+    pub fn new(planet: &Planetesimal, stellar_luminosity: &f64) -> Self {
+        let Planetesimal {
+            a,
+            e,
+            axial_tilt,
+            orbital_period_days,
+            albedo,
+            hydrosphere,
+            ice_cover,
+            surface_pressure_bar,
+            surface_temp_kelvin,
+            is_gas_giant,
+            seasons,
+            ..
+        } = planet;
+        if *is_gas_giant || *surface_temp_kelvin <= 0.0 || *orbital_period_days <= 0.0 {
+            return Self::default();
+        }
+
+        let positions: Vec<(f64, f64)> = (0..ANNUAL_STEPS)
+            .map(|step| {
+                let day = step as f64 * orbital_period_days / ANNUAL_STEPS as f64;
+                orbital_position(
+                    &day,
+                    orbital_period_days,
+                    e,
+                    &seasons.longitude_of_perihelion,
+                )
+            })
+            .collect();
+        let band_count = (180.0 / BAND_WIDTH) as usize;
+        let latitudes: Vec<f64> = (0..band_count)
+            .map(|band| -90.0 + BAND_WIDTH * (band as f64 + 0.5))
+            .collect();
+        let weights: Vec<f64> = latitudes
+            .iter()
+            .map(|latitude| latitude.to_radians().cos())
+            .collect();
+        let weights_sum: f64 = weights.iter().sum();
+        let global_mean = |values: &[f64]| {
+            values
+                .iter()
+                .zip(weights.iter())
+                .map(|(v, w)| v * w)
+                .sum::<f64>()
+                / weights_sum
+        };
+        let annual_insolation: Vec<f64> = latitudes
+            .iter()
+            .map(|latitude| {
+                positions
+                    .iter()
+                    .map(|(solar_longitude, distance)| {
+                        insolation(
+                            stellar_luminosity,
+                            &(a * distance),
+                            axial_tilt,
+                            latitude,
+                            solar_longitude,
+                        )
+                    })
+                    .sum::<f64>()
+                    / ANNUAL_STEPS as f64
+            })
+            .collect();
+        let global_insolation = global_mean(&annual_insolation);
+
+        // Outgoing radiation A + B * T, with A absorbing greenhouse rise of the planet
+        let olr_base = global_insolation * (1.0 - albedo) - OLR_SLOPE * surface_temp_kelvin;
+        let transport = EARTH_HEAT_TRANSPORT * surface_pressure_bar;
+        let has_water = *hydrosphere > 0.0 || *ice_cover > 0.0;
+
+        let mut temps = vec![*surface_temp_kelvin; band_count];
+        let mut albedos = vec![*albedo; band_count];
+        for _ in 0..MAX_ITERATIONS {
+            let global_temp = global_mean(&temps);
+            let next_temps: Vec<f64> = annual_insolation
+                .iter()
+                .zip(albedos.iter())
+                .map(|(q, band_albedo)| {
+                    (q * (1.0 - band_albedo) - olr_base + transport * global_temp)
+                        / (OLR_SLOPE + transport)
+                })
+                .collect();
+            let change = temps
+                .iter()
+                .zip(next_temps.iter())
+                .fold(0.0_f64, |acc, (t1, t2)| acc.max((t1 - t2).abs()));
+            temps = next_temps;
+            let ice: Vec<bool> = temps
+                .iter()
+                .map(|temp| has_water && *temp < FREEZING_POINT_OF_WATER)
+                .collect();
+            let ice_area = global_mean(&ice.iter().map(|i| *i as u8 as f64).collect::<Vec<f64>>());
+            // Global albedo of the planet already includes its ice caps, open surface is darker
+            let open_albedo = match ice_area < 1.0 {
+                true => ((albedo - ice_area * EBM_ICE_ALBEDO) / (1.0 - ice_area))
+                    .max(OPEN_SURFACE_MIN_ALBEDO)
+                    .min(*albedo),
+                false => *albedo,
+            };
+            albedos = ice
+                .iter()
+                .map(|ice| match ice {
+                    true => EBM_ICE_ALBEDO.max(*albedo),
+                    false => open_albedo,
+                })
+                .collect();
+            if change < 0.01 {
+                break;
+            }
+        }
+
+        let bands: Vec<LatitudeBand> = latitudes
+            .iter()
+            .enumerate()
+            .map(|(i, latitude)| {
+                let ice = has_water && temps[i] < FREEZING_POINT_OF_WATER;
+                let zone = match temps[i] {
+                    t if ice || t < FREEZING_POINT_OF_WATER => ClimateZone::Polar,
+                    t if t >= TROPICAL_TEMP => ClimateZone::Tropical,
+                    _ => ClimateZone::Temperate,
+                };
+                LatitudeBand {
+                    latitude: *latitude,
+                    insolation: float_to_precision(annual_insolation[i]),
+                    albedo: float_to_precision(albedos[i]),
+                    temp_kelvin: float_to_precision(temps[i]),
+                    ice,
+                    zone,
+                }
+            })
+            .collect();
+
+        let ice_line = |hemisphere: &dyn Fn(&LatitudeBand) -> bool| {
+            bands
+                .iter()
+                .filter(|band| hemisphere(band) && band.ice)
+                .map(|band| band.latitude.abs() - BAND_WIDTH / 2.0)
+                .fold(90.0, f64::min)
+        };
+        let ice_line_north = ice_line(&|band| band.latitude > 0.0);
+        let ice_line_south = ice_line(&|band| band.latitude < 0.0);
+        let absorbed = global_mean(
+            &annual_insolation
+                .iter()
+                .zip(albedos.iter())
+                .map(|(q, band_albedo)| q * (1.0 - band_albedo))
+                .collect::<Vec<f64>>(),
+        );
+
+        Self {
+            is_snowball: bands.iter().all(|band| band.ice),
+            is_runaway_greenhouse: has_water && absorbed > RUNAWAY_GREENHOUSE_FLUX,
+            global_temp_kelvin: float_to_precision(global_mean(&temps)),
+            ice_line_north,
+            ice_line_south,
+            bands,
+        }
+    }
+}
+
+/// Climate state for flux relative to Earth's, stellar surface temperature in Kelvin and planet mass in Earth masses.
+/// Greenhouse limits of flux come first, then surface temperature below freezing point marks frozen planet. Warm planet beyond the outer edge of the habitable zone is in maximum greenhouse.
+/// Without known surface temperature (0.0) the state follows flux alone, below early Mars limit the planet is frozen.
+pub fn climate_state(
+    flux: &f64,
+    stellar_surface_temp: &f64,
+    earth_masses: &f64,
+    surface_temp_kelvin: &f64,
+) -> ClimateState {
+    let limit = |limit: HabitableZoneLimit| effective_flux(&limit, stellar_surface_temp);
+    match *flux {
+        f if f >= runaway_greenhouse_flux(stellar_surface_temp, earth_masses) => {
+            ClimateState::RunawayGreenhouse
+        }
+        f if f >= limit(HabitableZoneLimit::MoistGreenhouse) => ClimateState::MoistGreenhouse,
+        _ if *surface_temp_kelvin > 0.0 && *surface_temp_kelvin < FREEZING_POINT_OF_WATER => {
+            ClimateState::Frozen
+        }
+        f if *surface_temp_kelvin == 0.0 && f < limit(HabitableZoneLimit::EarlyMars) => {
+            ClimateState::Frozen
+        }
+        f if f < limit(HabitableZoneLimit::MaximumGreenhouse) => ClimateState::MaximumGreenhouse,
+        _ => ClimateState::Temperate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn earth(albedo: f64) -> Planetesimal {
+        let mut earth = Planetesimal::on_orbit(1.0, 0.0167, "earth".to_string());
+        earth.axial_tilt = 23.44;
+        earth.orbital_period_days = 365.25;
+        earth.albedo = albedo;
+        earth.hydrosphere = 0.7;
+        earth.surface_pressure_bar = 1.0;
+        earth.surface_temp_kelvin = 288.0;
+        earth.seasons.longitude_of_perihelion = 283.0;
+        earth
+    }
+
+    #[test]
+    fn earth_ice_caps() {
+        let climate = Climate::new(&earth(0.3), &1.0);
+        assert!(!climate.is_snowball && !climate.is_runaway_greenhouse);
+        assert!(climate.ice_line_north > 55.0 && climate.ice_line_north < 90.0);
+        assert!(climate.ice_line_south > 55.0 && climate.ice_line_south < 90.0);
+        let equator = climate.bands[climate.bands.len() / 2];
+        assert_eq!(equator.zone, ClimateZone::Tropical);
+        assert!((climate.global_temp_kelvin - 288.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn cold_earth_freezes_over() {
+        let mut cold = earth(0.3);
+        cold.surface_temp_kelvin = 250.0;
+        let climate = Climate::new(&cold, &1.0);
+        assert!(climate.is_snowball);
+        assert_eq!(climate.ice_line_north, 0.0);
+    }
+
+    #[test]
+    fn solar_system_climate_states() {
+        let state = |distance: f64, temp: f64| {
+            climate_state(&(1.0 / distance.powf(2.0)), &5780.0, &1.0, &temp)
+        };
+        assert_eq!(state(0.723, 0.0), ClimateState::RunawayGreenhouse);
+        assert_eq!(state(1.0, 288.0), ClimateState::Temperate);
+        assert_eq!(state(1.0, 250.0), ClimateState::Frozen);
+        assert_eq!(state(1.524, 210.0), ClimateState::Frozen);
+        assert_eq!(state(1.7, 280.0), ClimateState::MaximumGreenhouse);
+        assert_eq!(state(5.2, 0.0), ClimateState::Frozen);
+    }
+}
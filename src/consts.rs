@@ -86,6 +86,7 @@ pub const WATER_ALBEDO: f64 = 0.04;
 pub const AIRLESS_ICE_ALBEDO: f64 = 0.5;
 pub const ICE_ALBEDO: f64 = 0.7;
 pub const SECONDS_PER_HOUR: f64 = 3600.0;
+pub const SECONDS_PER_YEAR: f64 = SECONDS_PER_HOUR * 24.0 * DAYS_IN_A_YEAR;
 
 pub const CM_PER_AU: f64 = 1.495978707E13;
 pub const CM_PER_KM: f64 = 1.0E5;
@@ -99,9 +100,6 @@ pub const KELVIN_CELCIUS_DIFFERENCE: f64 = 273.0;
 /// units of dyne cm2/gram2
 pub const GRAV_CONSTANT: f64 = 6.672E-8;
 
-/// affects inner radius..
-pub const GREENHOUSE_EFFECT_CONST: f64 = 0.93;
-
 /// units of g*m2/(sec2*K*mol)
 pub const MOLAR_GAS_CONST: f64 = 8314.41;
 
@@ -122,6 +120,9 @@ pub const ALPHA: f64 = 5.0;
 /// Used in calculating dust density (as the nth root of the radius, r)
 pub const N: f64 = 3.0;
 
+/// Water ice condensation distance for the Sun in AU (Hayashi 1981), scales with square root of luminosity
+pub const SNOW_LINE_AU: f64 = 2.7;
+
 /// Used in day_hours-length calcs (cm2/sec2 g)
 pub const J: f64 = 1.46E-19;
 
@@ -238,13 +239,70 @@ pub const EARTH_DENSITY: f64 = 5.52;
 /// Maximum angular inclination of dust cloud (Dole specifies as ~90 degrees)
 pub const OMEGA: f64 = PI / 2.01;
 
-/// Units of radians/sec/year
-pub const CHANGE_IN_EARTH_ANG_VEL: f64 = -1.3e-15;
-
 pub const RADIANS_PER_ROTATION: f64 = 2.0 * PI;
 
 /// Stefan–Boltzmann constant, W⋅m−2⋅K−4
 pub const SIGMA: f64 = 5.670374419e-8;
 
+/// Absolute visual magnitude of the Sun
+pub const SUN_ABSOLUTE_MAGNITUDE: f64 = 4.83;
+
+/// Effective temperature of the Sun, Kelvin
+pub const SUN_EFFECTIVE_TEMP: f64 = 5772.0;
+
+pub const AU_PER_PARSEC: f64 = 206264.806;
+
+/// Planck constant, J⋅s
+pub const PLANCK_CONSTANT: f64 = 6.62607015e-34;
+
+/// Boltzmann constant, J⋅K−1
+pub const BOLTZMANN_CONSTANT: f64 = 1.380649e-23;
+
+/// Speed of light, m/s
+pub const SPEED_OF_LIGHT: f64 = 2.99792458e8;
+
 /// Watt per 1 solar luminosity
 pub const WATT_PER_SOLAR_LUMINOSITY: f64 = 3.828e26;
+
+/// Erg/s per 1 solar luminosity
+pub const ERG_PER_SEC_PER_SOLAR_LUMINOSITY: f64 = WATT_PER_SOLAR_LUMINOSITY * 1.0e7;
+
+/// Units of years
+pub const EARTH_AGE: f64 = 4.5e9;
+pub const SOLAR_AGE: f64 = 4.57e9;
+pub const UNIVERSE_AGE: f64 = 1.38e10;
+
+/// Present-day heat production of Earth's bulk silicate, W/kg
+pub const EARTH_RADIOGENIC_HEAT_PER_KG: f64 = 5.0e-12;
+
+/// Ratio of radiogenic heat to total heat loss of Earth, rest is secular cooling
+pub const EARTH_UREY_RATIO: f64 = 0.45;
+
+/// Units of degrees Kelvin
+pub const EARTH_CENTRAL_TEMP: f64 = 5600.0;
+pub const JUPITER_CENTRAL_TEMP: f64 = 24000.0;
+
+pub const JUPITER_MASS_IN_EARTH_MASSES: f64 = 317.8;
+/// Equatorial radius of Jupiter, unit used by exoplanet catalogues
+pub const JUPITER_RADIUS_IN_EARTH_RADII: f64 = 11.209;
+
+/// Mean surface heat flow of Earth, W/m2
+pub const EARTH_HEAT_FLUX: f64 = 0.087;
+
+/// Units of km
+pub const EARTH_CORE_RADIUS_IN_KM: f64 = 3480.0;
+
+/// Equatorial surface field of Earth, gauss
+pub const EARTH_SURFACE_FIELD: f64 = 0.31;
+
+/// Day side magnetopause distance of Earth, Earth radii
+pub const EARTH_MAGNETOPAUSE_RADII: f64 = 10.0;
+
+/// Total mass of regular satellites relative to their giant planet
+pub const REGULAR_MOONS_MASS_RATIO: f64 = 1.0e-4;
+
+/// Share of primordial dust mass that survives collisional grinding and radiation pressure as belt bodies
+pub const BELT_SURVIVING_FRACTION: f64 = 1.0e-3;
+
+/// Stellar flux at 1 AU from the Sun, W/m2
+pub const SOLAR_CONSTANT: f64 = 1361.0;
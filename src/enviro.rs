@@ -1,5 +1,6 @@
 use rand::RngCore;
 
+use crate::climate::ClimateState;
 use crate::consts::*;
 use crate::structs::planetesimal::*;
 use crate::utils::*;
@@ -104,59 +105,6 @@ pub fn period(separation: &f64, small_mass: &f64, large_mass: &f64) -> f64 {
     float_to_precision(period_in_years * DAYS_IN_A_YEAR)
 }
 
-/// Fogg's information for this routine came from Dole "Habitable Planets for Man", Blaisdell Publishing Company, NY, 1964. From this, he came up with his eq.12, which is the equation for the base_angular_velocity below.
-/// Going a bit further, he found an equation for the change in angular velocity per time (dw/dt) from P. Goldreich and S. Soter's paper "Q in the Solar System" in Icarus, vol 5, pp.375-389 (1966).
-/// Comparing to the change in angular velocity for the Earth, we can come up with an approximation for our new planet (his eq.13) and take that into account.
-pub fn day_length(planet: &mut Planetesimal, stellar_mass: &f64, main_sequence_age: &f64) -> f64 {
-    let planet_mass_in_grams = planet.mass * SOLAR_MASS_IN_GRAMS;
-    let equatorial_radius_in_cm = planet.radius * CM_PER_KM;
-    let year_in_hours = planet.orbital_period_days;
-
-    let k2 = match planet.is_gas_giant {
-        true => 0.24,
-        false => 0.33,
-    };
-
-    let base_angular_velocity =
-        (2.0 * J * planet_mass_in_grams).sqrt() / (k2 * equatorial_radius_in_cm.powf(2.0));
-
-    let change_in_angular_velocity = CHANGE_IN_EARTH_ANG_VEL
-        * (planet.density / EARTH_DENSITY)
-        * (equatorial_radius_in_cm / EARTH_RADIUS_IN_CM)
-        * (EARTH_MASS_IN_GRAMS / planet_mass_in_grams)
-        * stellar_mass.powf(2.0)
-        * (1.0 / planet.a.powf(6.0));
-
-    let ang_velocity = base_angular_velocity + (change_in_angular_velocity * main_sequence_age);
-
-    let (stopped, day_in_hours) = match ang_velocity <= 0.0 {
-        true => (true, INCREDIBLY_LARGE_NUMBER),
-        false => (
-            false,
-            RADIANS_PER_ROTATION / (SECONDS_PER_HOUR * ang_velocity),
-        ),
-    };
-
-    if day_in_hours >= year_in_hours || stopped {
-        if planet.e > 0.1 {
-            let spin_resonance_factor = (1.0 - planet.e) / (1.0 + planet.e);
-            planet.resonant_period = true;
-            return spin_resonance_factor * year_in_hours;
-        } else {
-            return year_in_hours;
-        }
-    }
-    
-    float_to_precision(day_in_hours)
-}
-
-/// The orbital radius is expected in units of Astronomical Units (AU).
-/// Inclination is returned in units of degrees.
-pub fn inclination(orbital_radius: &f64, rng: &mut dyn RngCore) -> f64 {
-    let inclination = orbital_radius.powf(0.2) * about(EARTH_AXIAL_TILT, 0.4, rng);
-    float_to_precision(inclination % 360.0)
-}
-
 /// This function implements the escape velocity calculation. Note that it appears that Fogg's eq.15 is i/ncorrect.
 /// The mass is in units of solar mass, the radius in kilometers, and the velocity returned is in cm/sec.
 pub fn escape_vel(mass: &f64, radius: &f64) -> f64 {
@@ -173,16 +121,6 @@ pub fn rms_vel(molecular_weight: &f64, orbital_radius: &f64) -> f64 {
     float_to_precision(rms_vel)
 }
 
-/// This function returns the smallest molecular weight retained by the body, which is useful for determining the atmosphere composition. Orbital radius is in A.U.(ie: in units of the earth's orbital radius), mass is in units of solar masses, and equatorial radius is in units of kilometers.
-pub fn molecule_limit(mass: &f64, equatorial_radius: &f64) -> f64 {
-    let escape_velocity = escape_vel(mass, equatorial_radius);
-    let molecule_weight = 3.0 * (GAS_RETENTION_THRESHOLD * CM_PER_METER).powf(2.0)
-        * MOLAR_GAS_CONST
-        * EARTH_EXOSPHERE_TEMP
-        / escape_velocity.powf(2.0);
-    float_to_precision(molecule_weight)
-}
-
 /// This function calculates the surface acceleration of a planet. The mass is in units of solar masses, the radius in terms of km, and the acceleration is returned in units of cm/sec2.
 pub fn acceleration(mass: &f64, radius: &f64) -> f64 {
     float_to_precision(GRAV_CONSTANT * mass * SOLAR_MASS_IN_GRAMS / (radius * CM_PER_KM).powf(2.0))
@@ -193,25 +131,15 @@ pub fn gravity(acceleration: &f64) -> f64 {
     float_to_precision(acceleration / EARTH_ACCELERATION)
 }
 
-/// Note that if the orbital radius of the planet is greater than or equal to R_inner, 99% of it's volatiles are assumed to have been deposited in surface reservoirs (otherwise, it suffers from the greenhouse effect).
-pub fn greenhouse(
-    distance_to_primary_star: &f64,
-    orbit_zone: &i32,
-    surface_pressure_bar: &f64,
-    ecosphere_radius: &f64,
-) -> bool {
-    let greenhouse_radius = ecosphere_radius * GREENHOUSE_EFFECT_CONST;
-    *distance_to_primary_star < greenhouse_radius && *orbit_zone == 1 && *surface_pressure_bar > 0.0
-}
-
 /// This implements Fogg's eq.17. The 'inventory' returned is unitless.
+/// Planet in runaway greenhouse keeps its volatiles in the atmosphere, otherwise 99% of them are deposited in surface reservoirs.
 pub fn vol_inventory(
     mass: &f64,
     escape_vel: &f64,
     rms_vel: &f64,
     stellar_mass: &f64,
     zone: &i32,
-    greenhouse_effect: &bool,
+    climate_state: &ClimateState,
     rng: &mut dyn RngCore,
 ) -> f64 {
     let velocity_ratio = escape_vel / rms_vel;
@@ -231,7 +159,7 @@ pub fn vol_inventory(
     let temp1 = proportion_const * mass_in_earth_units / stellar_mass;
     let temp2 = about(temp1, 0.2, rng);
 
-    if *greenhouse_effect {
+    if *climate_state == ClimateState::RunawayGreenhouse {
         return temp2;
     }
 
@@ -417,25 +345,38 @@ pub fn get_earth_mass(mass: f64) -> f64 {
     float_to_precision(mass * EARTH_MASSES_PER_SOLAR_MASS)
 }
 
+/// Surface temperature iterations before the temperature is accepted as settled.
+const MAX_SURFACE_TEMP_ITERATIONS: usize = 100;
+
 /// The temperature calculated is in degrees Kelvin.
+/// Borderline planets can flip between wet and dry albedo on each step, after iteration limit the temperature settles between the last two values. Returns whether iteration converged.
 pub fn iterate_surface_temp(
     planet: &mut Planetesimal,
     ecosphere_radius: &f64,
     rng: &mut dyn RngCore,
-) {
+) -> bool {
     let mut albedo = 0.0;
     let mut water = 0.0;
     let mut clouds = 0.0;
     let mut ice = 0.0;
 
     let mut optical_depth = opacity(planet.molecule_weight, planet.surface_pressure_bar);
-    let mut effective_temp = eff_temp(ecosphere_radius, &planet.a, &EARTH_ALBEDO);
+    let mut effective_temp = eff_temp(
+        ecosphere_radius,
+        &planet.distance_to_primary_star,
+        &EARTH_ALBEDO,
+    );
     let mut greenhouse_rise =
         green_rise(optical_depth, effective_temp, planet.surface_pressure_bar);
     let mut surface_temp_kelvin = effective_temp + greenhouse_rise;
     let mut previous_temp = surface_temp_kelvin - 5.0;
 
-    while (surface_temp_kelvin - previous_temp).abs() > 1.0 {
+    let mut iterations = 0;
+
+    while (surface_temp_kelvin - previous_temp).abs() > 1.0
+        && iterations < MAX_SURFACE_TEMP_ITERATIONS
+    {
+        iterations += 1;
         previous_temp = surface_temp_kelvin;
         water = hydrosphere_fraction(&planet.volatile_gas_inventory, &planet.radius);
         clouds = cloud_fraction(
@@ -453,20 +394,20 @@ pub fn iterate_surface_temp(
         }
         albedo = planet_albedo(&water, &clouds, &ice, &planet.surface_pressure_bar, rng);
         optical_depth = opacity(planet.molecule_weight, planet.surface_pressure_bar);
-        effective_temp = eff_temp(ecosphere_radius, &planet.a, &albedo);
+        effective_temp = eff_temp(ecosphere_radius, &planet.distance_to_primary_star, &albedo);
         greenhouse_rise = green_rise(optical_depth, effective_temp, planet.surface_pressure_bar);
         surface_temp_kelvin = effective_temp + greenhouse_rise;
     }
+    let converged = (surface_temp_kelvin - previous_temp).abs() <= 1.0;
+    if !converged {
+        surface_temp_kelvin = (surface_temp_kelvin + previous_temp) / 2.0;
+    }
     planet.hydrosphere = water;
     planet.cloud_cover = clouds;
     planet.ice_cover = ice;
     planet.albedo = albedo;
     planet.surface_temp_kelvin = float_to_precision(surface_temp_kelvin);
-}
-
-pub fn check_tidal_lock(day_length: f64, orbital_period: f64) -> bool {
-    let error_margin = f64::EPSILON;
-    (day_length - orbital_period * day_length).abs() < error_margin
+    converged
 }
 
 fn lim(x: f64) -> f64 {
@@ -0,0 +1,230 @@
+use crate::activity::StellarActivity;
+use crate::consts::*;
+use crate::structs::Planetesimal;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Gases followed by escape model, from hydrogen envelope to heavy secondary atmosphere.
+const GASES: [f64; 9] = [
+    MOLECULAR_HYDROGEN,
+    HELIUM,
+    METHANE,
+    AMMONIA,
+    WATER_VAPOR,
+    MOLECULAR_NITROGEN,
+    MOLECULAR_OXYGEN,
+    ARGON,
+    CARBON_DIOXIDE,
+];
+
+/// Gases making secondary atmosphere of rocky planets, their retention scales volatile inventory.
+/// Water is left out, as cold trap keeps it below tropopause until runaway greenhouse.
+const SECONDARY_ATMOSPHERE: [f64; 2] = [MOLECULAR_NITROGEN, CARBON_DIOXIDE];
+
+/// Thermosphere heats less than in proportion to XUV flux, as radiative cooling by CO2 and atomic lines grows with temperature.
+const EXOSPHERE_XUV_INDEX: f64 = 0.2;
+
+/// Share of absorbed XUV energy driving hydrodynamic escape.
+/// [Watson, Donahue & Walker 1981, The dynamics of a rapidly escaping atmosphere](https://doi.org/10.1016/0019-1035(81)90101-9)
+const HYDRODYNAMIC_EFFICIENCY: f64 = 0.15;
+
+/// Share of one gas left after escape.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GasRetention {
+    pub molecular_weight: f64,
+    // gravitational to thermal energy at the exobase at system age
+    pub jeans_parameter: f64,
+    pub retained_fraction: f64,
+}
+
+/// Atmosphere lost to space over system age.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AtmosphericEscape {
+    // at system age, units of Kelvin
+    pub exosphere_temp_kelvin: f64,
+    pub gases: Vec<GasRetention>,
+    // lightest gas with more than half of it retained
+    pub smallest_retained_weight: f64,
+    // hydrogen and helium envelope evaporated by XUV heating, Earth masses
+    pub envelope_mass_lost: f64,
+    pub envelope_stripped: bool,
+}
+
+impl AtmosphericEscape {
+    /// Escape from a body with derived radius and XUV exposure over system age. Exosphere temperature follows XUV flux of aging star, each gas escapes thermally at Jeans rate.
+    /// With hydrodynamic escape XUV dose evaporates hydrogen and helium envelope at energy-limited rate.
+    pub fn new(
+        planet: &Planetesimal,
+        activity: &StellarActivity,
+        hydrodynamic_escape: &bool,
+    ) -> Self {
+        let Planetesimal {
+            mass,
+            radius,
+            composition,
+            distance_to_primary_star,
+            xuv,
+            ..
+        } = planet;
+        if *radius <= 0.0 || *mass <= 0.0 {
+            return Self::default();
+        }
+        let escape_velocity_squared = (2.0 * GRAV_CONSTANT * mass * SOLAR_MASS_IN_GRAMS
+            / (radius * CM_PER_KM))
+            / CM_PER_METER.powf(2.0);
+        // exosphere temperature and length of each step of activity history
+        let present_solar_xuv = StellarActivity::solar().xuv_flux(&1.0, &EARTH_AGE);
+        let steps: Vec<(f64, f64)> = activity
+            .history()
+            .iter()
+            .map(|(time, duration)| {
+                let relative_xuv =
+                    activity.xuv_flux(distance_to_primary_star, time) / present_solar_xuv;
+                (exosphere_temp(&relative_xuv), *duration)
+            })
+            .collect();
+        // Newborn star has no history, exosphere is heated by its present flux
+        let present_exosphere_temp = steps.last().map_or_else(
+            || {
+                exosphere_temp(
+                    &(activity.xuv_flux(distance_to_primary_star, &activity.age)
+                        / present_solar_xuv),
+                )
+            },
+            |(temp, _)| *temp,
+        );
+
+        let retention_threshold = 1.5 * GAS_RETENTION_THRESHOLD.powf(2.0);
+        let gases: Vec<GasRetention> = GASES
+            .iter()
+            .map(|weight| {
+                let jeans = |temp: &f64| jeans_parameter(&escape_velocity_squared, weight, temp);
+                let escaped: f64 = steps
+                    .iter()
+                    .map(|(temp, duration)| {
+                        duration / jeans_escape_timescale(&jeans(temp), &retention_threshold)
+                    })
+                    .sum();
+                GasRetention {
+                    molecular_weight: *weight,
+                    jeans_parameter: float_to_precision(jeans(&present_exosphere_temp)),
+                    retained_fraction: float_to_precision((-escaped).exp()),
+                }
+            })
+            .collect();
+        let smallest_retained_weight = gases
+            .iter()
+            .find(|gas| gas.retained_fraction > 0.5)
+            .map_or(INCREDIBLY_LARGE_NUMBER, |gas| gas.molecular_weight);
+
+        let envelope_mass = composition.gas * mass * EARTH_MASSES_PER_SOLAR_MASS;
+        let envelope_mass_lost = match *hydrodynamic_escape && envelope_mass > 0.0 {
+            true => {
+                let lost =
+                    energy_limited_mass_loss(&xuv.xuv_dose, radius, mass) / EARTH_MASS_IN_GRAMS;
+                lost.min(envelope_mass)
+            }
+            false => 0.0,
+        };
+
+        Self {
+            exosphere_temp_kelvin: float_to_precision(present_exosphere_temp),
+            gases,
+            smallest_retained_weight,
+            envelope_mass_lost: float_to_precision(envelope_mass_lost),
+            envelope_stripped: envelope_mass > 0.0 && envelope_mass_lost >= envelope_mass,
+        }
+    }
+
+    /// Share of secondary atmosphere (nitrogen and carbon dioxide) left after escape.
+    pub fn volatile_retention(&self) -> f64 {
+        let retained: Vec<f64> = self
+            .gases
+            .iter()
+            .filter(|gas| SECONDARY_ATMOSPHERE.contains(&gas.molecular_weight))
+            .map(|gas| gas.retained_fraction)
+            .collect();
+        match retained.is_empty() {
+            true => 1.0,
+            false => retained.iter().sum::<f64>() / retained.len() as f64,
+        }
+    }
+}
+
+/// Exosphere temperature in Kelvin under XUV flux relative to present Sun at Earth, Earth's exosphere as reference.
+pub fn exosphere_temp(relative_xuv_flux: &f64) -> f64 {
+    EARTH_EXOSPHERE_TEMP * relative_xuv_flux.powf(EXOSPHERE_XUV_INDEX)
+}
+
+/// Jeans escape parameter, escape velocity squared in m2/sec2 to thermal speed squared of gas with molecular weight at exosphere temperature.
+pub fn jeans_parameter(
+    escape_velocity_squared: &f64,
+    molecular_weight: &f64,
+    exosphere_temp: &f64,
+) -> f64 {
+    escape_velocity_squared * molecular_weight / (2.0 * MOLAR_GAS_CONST * exosphere_temp)
+}
+
+/// E-folding time of gas (years) escaping at Jeans rate proportional to (1 + λ) exp(-λ). Gas at retention threshold lasts the age of Earth.
+pub fn jeans_escape_timescale(jeans_parameter: &f64, retention_threshold: &f64) -> f64 {
+    EARTH_AGE
+        * (jeans_parameter - retention_threshold).min(700.0).exp()
+        * (1.0 + retention_threshold)
+        / (1.0 + jeans_parameter)
+}
+
+/// Mass (grams) lost from planet of radius in km and mass in solar masses by energy-limited escape under XUV fluence in erg/cm2.
+/// [Erkaev et al. 2007, Roche lobe effects on the atmospheric loss from "Hot Jupiters"](https://doi.org/10.1051/0004-6361:20077612)
+pub fn energy_limited_mass_loss(fluence: &f64, radius: &f64, mass: &f64) -> f64 {
+    HYDRODYNAMIC_EFFICIENCY * PI * (radius * CM_PER_KM).powf(3.0) * fluence
+        / (GRAV_CONSTANT * mass * SOLAR_MASS_IN_GRAMS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::XuvExposure;
+
+    fn body(earth_masses: f64, radius: f64, distance: f64) -> Planetesimal {
+        let mut body = Planetesimal::on_orbit(distance, 0.0, "body".to_string());
+        body.mass = earth_masses / EARTH_MASSES_PER_SOLAR_MASS;
+        body.radius = radius;
+        body.xuv = XuvExposure::new(&distance, &StellarActivity::solar());
+        body
+    }
+
+    #[test]
+    fn earth_keeps_air_and_loses_hydrogen() {
+        let escape = AtmosphericEscape::new(
+            &body(1.0, EARTH_RADIUS_IN_KM, 1.0),
+            &StellarActivity::solar(),
+            &true,
+        );
+        let retained = |weight: f64| {
+            escape
+                .gases
+                .iter()
+                .find(|gas| gas.molecular_weight == weight)
+                .unwrap()
+                .retained_fraction
+        };
+        assert!(retained(MOLECULAR_HYDROGEN) < 0.01);
+        assert!(retained(MOLECULAR_NITROGEN) > 0.99);
+        assert!(escape.volatile_retention() > 0.99);
+        // The Moon at the same distance is a bare rock
+        let moon =
+            AtmosphericEscape::new(&body(0.0123, 1737.4, 1.0), &StellarActivity::solar(), &true);
+        assert!(moon.volatile_retention() < 0.01);
+    }
+
+    #[test]
+    fn hot_sub_neptune_loses_envelope() {
+        let mut sub_neptune = body(5.0, 2.5 * EARTH_RADIUS_IN_KM, 0.05);
+        sub_neptune.composition = sub_neptune.composition.with_gas(&0.02);
+        let escape = AtmosphericEscape::new(&sub_neptune, &StellarActivity::solar(), &true);
+        assert!(escape.envelope_stripped);
+        let without = AtmosphericEscape::new(&sub_neptune, &StellarActivity::solar(), &false);
+        assert!(!without.envelope_stripped && without.envelope_mass_lost == 0.0);
+    }
+}
@@ -23,6 +23,8 @@ pub enum AccreteEvent {
     MoonsCoalesced(String, String, String, Planetesimal),
     /// One planetesimal catch another as moon
     PlanetesimalCaptureMoon(String, String, String, Planetesimal),
+    /// Planetesimal crossing protected pinned planet scattered out of the system
+    PlanetesimalScattered(String, String, Planetesimal),
     /// Moons turned into rings
     PlanetesimalMoonToRing(String, String, String, Ring),
     /// Once at the very end of accretion
@@ -46,6 +48,7 @@ impl AccreteEvent {
             AccreteEvent::PlanetesimalsCoalesced(name, _, _, _) => name,
             AccreteEvent::MoonsCoalesced(name, _, _, _) => name,
             AccreteEvent::PlanetesimalCaptureMoon(name, _, _, _) => name,
+            AccreteEvent::PlanetesimalScattered(name, _, _) => name,
             AccreteEvent::PlanetesimalMoonToRing(name, _, _, _) => name,
             AccreteEvent::PostAccretionStarted(name) => name,
             AccreteEvent::OuterBodyInjected(name, _) => name,
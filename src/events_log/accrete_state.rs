@@ -67,6 +67,11 @@ impl AccreteState {
                 self.system.planets.push(planetesimal.clone());
             }
 
+            AccreteEvent::PlanetesimalScattered(_, _, planetesimal) => {
+                self.system.planets.retain(|p| p.id != planetesimal.id);
+                self.system.scattered_mass += planetesimal.mass;
+            }
+
             AccreteEvent::PlanetesimalMoonToRing(_, planet_id, moon_id, ring) => {
                 let planet = self.find_planetesimal_mut(planet_id);
                 planet.moons = planet
@@ -74,6 +74,15 @@ impl EventSource for Planetesimal {
             ));
         }
 
+        if event_type.contains("planetesimal_scattered") {
+            let data: Vec<&str> = event_type.split(':').collect();
+            event = Some(AccreteEvent::PlanetesimalScattered(
+                data[0].to_string(),
+                data[1].to_string(),
+                self.clone(),
+            ));
+        }
+
         if event_type.contains("planetesimal_capture_moon") {
             let data: Vec<&str> = event_type.split(':').collect();
             event = Some(AccreteEvent::PlanetesimalCaptureMoon(
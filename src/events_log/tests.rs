@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::events_log::accrete_state::AccreteState;
-    use crate::Accrete;
+    use crate::{Accrete, PinnedPlanet};
 
     #[test]
     fn restore_state_default() {
@@ -20,4 +20,28 @@ mod tests {
             format!("{:?}", accrete_state.system)
         );
     }
+
+    #[test]
+    fn restore_state_with_protected_pin() {
+        let mut accrete = Accrete::new(4);
+        accrete.stellar_mass = 1.0;
+        accrete.post_accretion_intensity = 0;
+        accrete.pinned_planets = vec![PinnedPlanet::new(5.2, 0.048, 317.8)];
+        let resulting_system = accrete.planetary_system();
+        assert!(accrete
+            .events_log
+            .iter()
+            .any(|e| e.name() == "planetesimal_scattered"));
+        let mut accrete_state = AccreteState::try_from(&accrete.events_log[0])
+            .expect("Failed to restore Accrete state.");
+
+        for e in accrete.events_log.iter() {
+            accrete_state.set_from_event(e);
+        }
+
+        assert_eq!(
+            format!("{:?}", resulting_system),
+            format!("{:?}", accrete_state.system)
+        );
+    }
 }
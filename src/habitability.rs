@@ -0,0 +1,104 @@
+use crate::activity::XuvExposure;
+use crate::climate::ClimateState;
+use crate::structs::Planetesimal;
+
+use serde::{Deserialize, Serialize};
+
+/// Lowest surface pressure (bar) with stable liquid water, triple point of water.
+const MIN_HABITABLE_PRESSURE: f64 = 0.006;
+
+/// XUV dose relative to Earth's, above which planet loses its oceans to hydrogen escape while young star is saturated.
+/// [Luger & Barnes 2015, Extreme water loss and abiotic O2 buildup on planets throughout the habitable zones of M dwarfs](https://doi.org/10.1089/ast.2014.1231)
+const MAX_HABITABLE_XUV_DOSE: f64 = 10.0;
+
+/// Ozone layer takes about two years to recover from great flare, more frequent flares keep surface exposed to UV.
+/// [Segura et al. 2010, The effect of a strong stellar flare on the atmospheric chemistry of an Earth-like planet orbiting an M dwarf](https://doi.org/10.1089/ast.2009.0376)
+const MAX_OZONE_DEPLETING_FLARES: f64 = 0.5;
+
+/// Reason of a body to be uninhabitable for surface life.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HabitabilityHazard {
+    NoSolidSurface,
+    ThinAtmosphere,
+    NoSurfaceWater,
+    Greenhouse,
+    Frozen,
+    // oceans lost to XUV-driven escape
+    XuvDose,
+    // ozone layer depleted by frequent flares
+    Flares,
+}
+
+/// Verdict on surface habitability.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Habitability {
+    pub is_habitable: bool,
+    pub hazards: Vec<HabitabilityHazard>,
+}
+
+impl Habitability {
+    /// Body with derived climate and XUV exposure is habitable when it holds liquid water under thick enough atmosphere, and its star neither dried it out nor keeps stripping its ozone.
+    pub fn new(planet: &Planetesimal) -> Self {
+        let Planetesimal {
+            is_gas_giant,
+            surface_pressure_bar,
+            hydrosphere,
+            climate_state,
+            xuv:
+                XuvExposure {
+                    relative_dose,
+                    ozone_depleting_flares,
+                    ..
+                },
+            ..
+        } = planet;
+        let mut hazards = Vec::new();
+        if *is_gas_giant {
+            hazards.push(HabitabilityHazard::NoSolidSurface);
+        } else {
+            if *surface_pressure_bar < MIN_HABITABLE_PRESSURE {
+                hazards.push(HabitabilityHazard::ThinAtmosphere);
+            }
+            if *hydrosphere <= 0.0 {
+                hazards.push(HabitabilityHazard::NoSurfaceWater);
+            }
+            match climate_state {
+                ClimateState::MoistGreenhouse | ClimateState::RunawayGreenhouse => {
+                    hazards.push(HabitabilityHazard::Greenhouse)
+                }
+                ClimateState::Frozen => hazards.push(HabitabilityHazard::Frozen),
+                _ => (),
+            }
+        }
+        if *relative_dose > MAX_HABITABLE_XUV_DOSE {
+            hazards.push(HabitabilityHazard::XuvDose);
+        }
+        if *ozone_depleting_flares > MAX_OZONE_DEPLETING_FLARES {
+            hazards.push(HabitabilityHazard::Flares);
+        }
+
+        Self {
+            is_habitable: hazards.is_empty(),
+            hazards,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flares_spoil_temperate_planet() {
+        let mut planet = Planetesimal::on_orbit(1.0, 0.0, "planet".to_string());
+        planet.surface_pressure_bar = 1.0;
+        planet.hydrosphere = 0.7;
+        planet.climate_state = ClimateState::Temperate;
+        planet.xuv.relative_dose = 1.0;
+        assert!(Habitability::new(&planet).is_habitable);
+
+        planet.xuv.ozone_depleting_flares = 10.0;
+        let flaring = Habitability::new(&planet);
+        assert!(!flaring.is_habitable && flaring.hazards == vec![HabitabilityHazard::Flares]);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::consts::SpectralClass;
+use crate::structs::primary_star::ecosphere;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Boundaries of the circumstellar habitable zone by climate of Earth-like planet with water, CO2 and N2 atmosphere.
+/// [Kopparapu et al. 2013, Habitable zones around main-sequence stars: new estimates](https://doi.org/10.1088/0004-637X/765/2/131)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HabitableZoneLimit {
+    // empirical inner edge, Venus has had no surface water for at least 1 Gyr
+    RecentVenus,
+    // oceans evaporate entirely
+    RunawayGreenhouse,
+    // water reaches stratosphere and is lost to space
+    MoistGreenhouse,
+    // CO2 clouds scatter more than CO2 warms
+    MaximumGreenhouse,
+    // empirical outer edge, Mars had surface water 3.8 Gyr ago
+    EarlyMars,
+}
+
+/// Distances of habitable zone limits from the star, AU.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HabitableZone {
+    // optimistic inner edge
+    pub recent_venus: f64,
+    // conservative inner edge
+    pub runaway_greenhouse: f64,
+    pub moist_greenhouse: f64,
+    // conservative outer edge
+    pub maximum_greenhouse: f64,
+    // optimistic outer edge
+    pub early_mars: f64,
+}
+
+/// Source of ecosphere used for planetary temperatures, available from configuration.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum HabitableZoneModel {
+    // conservative habitable zone from runaway to maximum greenhouse limits
+    #[default]
+    Kopparapu,
+    // flux factors per spectral class, classes without them use Kopparapu limits
+    Legacy,
+}
+
+/// Stellar temperatures (Kelvin) where flux limits are fitted.
+const FIT_MIN_TEMP: f64 = 2600.0;
+const FIT_MAX_TEMP: f64 = 7200.0;
+
+/// Runaway greenhouse limits of planets with 0.1, 1 and 5 Earth masses, heavier planets hold more water in atmosphere before they run away.
+/// [Kopparapu et al. 2014, Habitable zones around main-sequence stars: dependence on planetary mass](https://doi.org/10.1088/2041-8205/787/2/L29)
+const RUNAWAY_GREENHOUSE_BY_MASS: [(f64, [f64; 5]); 3] = [
+    (0.1, [0.99, 1.209e-4, 1.404e-8, -7.418e-12, -1.713e-15]),
+    (1.0, [1.107, 1.332e-4, 1.580e-8, -8.308e-12, -1.931e-15]),
+    (5.0, [1.188, 1.433e-4, 1.707e-8, -8.968e-12, -2.084e-15]),
+];
+
+impl HabitableZoneLimit {
+    /// S_eff at solar temperature and polynomial coefficients in difference of stellar temperature from solar.
+    fn coefficients(&self) -> [f64; 5] {
+        match self {
+            HabitableZoneLimit::RecentVenus => [1.776, 2.136e-4, 2.533e-8, -1.332e-11, -3.097e-15],
+            HabitableZoneLimit::RunawayGreenhouse => RUNAWAY_GREENHOUSE_BY_MASS[1].1,
+            HabitableZoneLimit::MoistGreenhouse => {
+                [1.0140, 8.1774e-5, 1.7063e-9, -4.3241e-12, -6.6462e-16]
+            }
+            HabitableZoneLimit::MaximumGreenhouse => {
+                [0.356, 6.171e-5, 1.698e-9, -3.198e-12, -5.575e-16]
+            }
+            HabitableZoneLimit::EarlyMars => [0.320, 5.547e-5, 1.526e-9, -2.874e-12, -5.011e-16],
+        }
+    }
+}
+
+impl HabitableZone {
+    /// Limits for stellar luminosity in solar units and stellar surface temperature in Kelvin.
+    pub fn new(stellar_luminosity: &f64, stellar_surface_temp: &f64) -> Self {
+        let distance = |limit: HabitableZoneLimit| {
+            limit_distance(&limit, stellar_luminosity, stellar_surface_temp)
+        };
+        Self {
+            recent_venus: distance(HabitableZoneLimit::RecentVenus),
+            runaway_greenhouse: distance(HabitableZoneLimit::RunawayGreenhouse),
+            moist_greenhouse: distance(HabitableZoneLimit::MoistGreenhouse),
+            maximum_greenhouse: distance(HabitableZoneLimit::MaximumGreenhouse),
+            early_mars: distance(HabitableZoneLimit::EarlyMars),
+        }
+    }
+}
+
+impl HabitableZoneModel {
+    /// Inner and outer radius of ecosphere, AU.
+    pub fn ecosphere(
+        &self,
+        habitable_zone: &HabitableZone,
+        stellar_luminosity: &f64,
+        spectral_class: &SpectralClass,
+    ) -> (f64, f64) {
+        let conservative = (
+            habitable_zone.runaway_greenhouse,
+            habitable_zone.maximum_greenhouse,
+        );
+        match self {
+            HabitableZoneModel::Kopparapu => conservative,
+            HabitableZoneModel::Legacy => {
+                ecosphere(stellar_luminosity, spectral_class).unwrap_or(conservative)
+            }
+        }
+    }
+}
+
+fn flux_polynomial(coefficients: &[f64; 5], stellar_surface_temp: &f64) -> f64 {
+    let t = stellar_surface_temp.clamp(FIT_MIN_TEMP, FIT_MAX_TEMP) - 5780.0;
+    let [s0, a, b, c, d] = coefficients;
+    s0 + a * t + b * t.powf(2.0) + c * t.powf(3.0) + d * t.powf(4.0)
+}
+
+/// Stellar flux at the limit relative to flux received by Earth, for stellar surface temperature in Kelvin.
+/// Temperatures outside of 2600-7200 K use the nearest fitted value.
+pub fn effective_flux(limit: &HabitableZoneLimit, stellar_surface_temp: &f64) -> f64 {
+    flux_polynomial(&limit.coefficients(), stellar_surface_temp)
+}
+
+/// Runaway greenhouse flux limit for planet mass in Earth masses, interpolated in logarithm of mass between fitted planets.
+pub fn runaway_greenhouse_flux(stellar_surface_temp: &f64, earth_masses: &f64) -> f64 {
+    let fluxes: Vec<(f64, f64)> = RUNAWAY_GREENHOUSE_BY_MASS
+        .iter()
+        .map(|(mass, coefficients)| {
+            (
+                mass.ln(),
+                flux_polynomial(coefficients, stellar_surface_temp),
+            )
+        })
+        .collect();
+    let log_mass = earth_masses
+        .clamp(
+            RUNAWAY_GREENHOUSE_BY_MASS[0].0,
+            RUNAWAY_GREENHOUSE_BY_MASS[2].0,
+        )
+        .ln();
+    let segment = match log_mass < fluxes[1].0 {
+        true => [fluxes[0], fluxes[1]],
+        false => [fluxes[1], fluxes[2]],
+    };
+    let [(m1, s1), (m2, s2)] = segment;
+    s1 + (s2 - s1) * (log_mass - m1) / (m2 - m1)
+}
+
+/// Distance to the limit in AU for stellar luminosity in solar units.
+pub fn limit_distance(
+    limit: &HabitableZoneLimit,
+    stellar_luminosity: &f64,
+    stellar_surface_temp: &f64,
+) -> f64 {
+    float_to_precision((stellar_luminosity / effective_flux(limit, stellar_surface_temp)).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solar_habitable_zone() {
+        let inner = limit_distance(&HabitableZoneLimit::MoistGreenhouse, &1.0, &5780.0);
+        let outer = limit_distance(&HabitableZoneLimit::MaximumGreenhouse, &1.0, &5780.0);
+        assert!((inner - 0.99).abs() < 0.01);
+        assert!((outer - 1.67).abs() < 0.01);
+        // Heavier planets run away closer to the star
+        let runaway_earth = runaway_greenhouse_flux(&5780.0, &1.0);
+        assert!((runaway_earth - 1.107).abs() < 1.0e-9);
+        assert!(runaway_greenhouse_flux(&5780.0, &3.0) > runaway_earth);
+        assert!(runaway_greenhouse_flux(&5780.0, &0.3) < runaway_earth);
+    }
+
+    #[test]
+    fn legacy_ecosphere_of_hot_star() {
+        let habitable_zone = HabitableZone::new(&100.0, &12000.0);
+        let ecosphere =
+            HabitableZoneModel::Legacy.ecosphere(&habitable_zone, &100.0, &SpectralClass::B);
+        assert!(ecosphere.0.is_finite() && ecosphere.1.is_finite());
+        assert_eq!(
+            ecosphere,
+            HabitableZoneModel::Kopparapu.ecosphere(&habitable_zone, &100.0, &SpectralClass::B)
+        );
+        assert!(habitable_zone.recent_venus < habitable_zone.runaway_greenhouse);
+        assert!(habitable_zone.maximum_greenhouse < habitable_zone.early_mars);
+    }
+}
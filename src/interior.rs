@@ -0,0 +1,214 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::Planetesimal;
+
+use serde::{Deserialize, Serialize};
+
+/// Layered interior of a planet. Mass fractions add up to one.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Interior {
+    // iron-nickel core
+    pub core_mass_fraction: f64,
+    // silicate mantle
+    pub mantle_mass_fraction: f64,
+    // differentiated silicate crust
+    pub crust_mass_fraction: f64,
+    // water and ice layers
+    pub ice_mass_fraction: f64,
+    // hydrogen and helium envelope
+    pub envelope_mass_fraction: f64,
+    // units of GPa
+    pub central_pressure: f64,
+    // units of Kelvin
+    pub central_temp_kelvin: f64,
+    // heat produced by decay of U, Th and K, TW
+    pub radiogenic_heat: f64,
+    // heat dissipated by tides raised by host body on eccentric orbit, TW
+    pub tidal_heat: f64,
+    // total heat flowing out through the surface, W/m2
+    pub surface_heat_flux: f64,
+    // convecting liquid core or metallic envelope able to sustain magnetic field
+    pub dynamo: bool,
+}
+
+/// Radiogenic isotopes: share of Earth's present-day radiogenic heat and half-life in years.
+/// [Turcotte & Schubert, Geodynamics, table 4.2](https://doi.org/10.1017/CBO9780511843877)
+const RADIOGENIC_ISOTOPES: [(f64, f64); 4] = [
+    // U238
+    (0.39, 4.468e9),
+    // U235
+    (0.017, 7.04e8),
+    // Th232
+    (0.40, 1.405e10),
+    // K40
+    (0.19, 1.248e9),
+];
+
+impl Interior {
+    /// Interior of a body with already derived radius and composition. Age is in years, host mass (solar masses) is the star for planets and the planet for moons.
+    pub fn new(planet: &Planetesimal, age: &f64, host_mass: &f64) -> Self {
+        let Planetesimal {
+            a,
+            e,
+            mass,
+            radius,
+            composition,
+            is_gas_giant,
+            ..
+        } = planet;
+        let earth_masses = mass * EARTH_MASSES_PER_SOLAR_MASS;
+        let surface_grav = gravity(&acceleration(mass, radius));
+
+        // Crust is thicker on low gravity bodies: Earth 0.5%, Mars 2%, Moon 7% of mass
+        let crust_mass_fraction =
+            (composition.rock * 0.007 * surface_grav.powf(-1.5)).min(composition.rock * 0.5);
+        let core_mass_fraction = composition.metal;
+        let mantle_mass_fraction = composition.rock - crust_mass_fraction;
+
+        let central_pressure = central_pressure(mass, radius, &core_mass_fraction, is_gas_giant);
+        let rocky_central_temp = EARTH_CENTRAL_TEMP * earth_masses.powf(0.5);
+        let central_temp_kelvin = match is_gas_giant {
+            true => (JUPITER_CENTRAL_TEMP
+                * (earth_masses / JUPITER_MASS_IN_EARTH_MASSES).powf(0.6))
+            .max(rocky_central_temp),
+            false => rocky_central_temp,
+        };
+
+        let silicate_mass_kg = mass * SOLAR_MASS_IN_GRAMS / 1000.0 * composition.rock;
+        let radiogenic_heat = radiogenic_heat(&silicate_mass_kg, age);
+        let tidal_heat = tidal_heat(host_mass, a, e, radius, is_gas_giant);
+        let surface_heat_flux =
+            surface_heat_flux(&(radiogenic_heat / EARTH_UREY_RATIO + tidal_heat), radius);
+
+        Self {
+            core_mass_fraction,
+            mantle_mass_fraction,
+            crust_mass_fraction,
+            ice_mass_fraction: composition.ice,
+            envelope_mass_fraction: composition.gas,
+            central_pressure,
+            central_temp_kelvin,
+            radiogenic_heat,
+            tidal_heat,
+            surface_heat_flux,
+            dynamo: false,
+        }
+    }
+}
+
+/// Hydrostatic central pressure of uniform sphere, scaled up by central condensation (Earth ~364 GPa, Jupiter ~4000 GPa). Mass in solar masses, radius in km, pressure returned in GPa.
+pub fn central_pressure(
+    mass: &f64,
+    radius: &f64,
+    core_mass_fraction: &f64,
+    is_gas_giant: &bool,
+) -> f64 {
+    let mass_in_grams = mass * SOLAR_MASS_IN_GRAMS;
+    let radius_in_cm = radius * CM_PER_KM;
+    let uniform =
+        3.0 * GRAV_CONSTANT * mass_in_grams.powf(2.0) / (8.0 * PI * radius_in_cm.powf(4.0));
+    let condensation = match is_gas_giant {
+        true => 3.5,
+        false => 1.0 + 3.5 * core_mass_fraction,
+    };
+    // dyne/cm2 to GPa
+    uniform * condensation * 1.0e-10
+}
+
+/// Heat (TW) released by radioactive decay in silicate mass (kg) of given age in years. Chondritic abundances give ~20 TW for Earth's mantle today.
+pub fn radiogenic_heat(silicate_mass_kg: &f64, age: &f64) -> f64 {
+    // Stars older than universe are not observed yet
+    let age = age.min(UNIVERSE_AGE);
+    let heat_per_kg = RADIOGENIC_ISOTOPES
+        .iter()
+        .fold(0.0, |acc, (share, half_life)| {
+            acc + share * (-(2.0_f64.ln()) * (age - EARTH_AGE) / half_life).exp()
+        })
+        * EARTH_RADIOGENIC_HEAT_PER_KG;
+    heat_per_kg * silicate_mass_kg / 1.0e12
+}
+
+/// Tidal dissipation (TW) in a synchronously rotating body on eccentric orbit, about 100 TW for Io. Host mass in solar masses, semi-major axis in AU, radius in km.
+/// [Peale, Cassen & Reynolds 1979, Melting of Io by Tidal Dissipation](https://doi.org/10.1126/science.203.4383.892)
+pub fn tidal_heat(host_mass: &f64, a: &f64, e: &f64, radius: &f64, is_gas_giant: &bool) -> f64 {
+    if *a <= 0.0 {
+        return 0.0;
+    }
+    // Love number over tidal quality factor
+    let k2_over_q = match is_gas_giant {
+        true => 1.0e-5,
+        false => 0.015,
+    };
+    let host_mass_in_grams = host_mass * SOLAR_MASS_IN_GRAMS;
+    let a_in_cm = a * CM_PER_AU;
+    let radius_in_cm = radius * CM_PER_KM;
+    let mean_motion = (GRAV_CONSTANT * host_mass_in_grams / a_in_cm.powf(3.0)).sqrt();
+    let erg_per_sec = 10.5
+        * k2_over_q
+        * GRAV_CONSTANT
+        * host_mass_in_grams.powf(2.0)
+        * radius_in_cm.powf(5.0)
+        * mean_motion
+        * e.powf(2.0)
+        / a_in_cm.powf(6.0);
+    // erg/s to TW
+    erg_per_sec * 1.0e-19
+}
+
+/// Heat flux through the surface in W/m2, heat in TW and radius in km.
+pub fn surface_heat_flux(heat: &f64, radius: &f64) -> f64 {
+    let radius_in_m = radius * 1000.0;
+    heat * 1.0e12 / (4.0 * PI * radius_in_m.powf(2.0))
+}
+
+/// Plate tectonics need enough internal heat to keep mantle convecting and water to weaken lithosphere, otherwise planet ends with stagnant lid like Venus or Mars.
+/// Very hot interiors (Io-like) break lithosphere even without water.
+pub fn tectonic_activity(planet: &Planetesimal) -> bool {
+    let heat_flux = planet.interior.surface_heat_flux;
+    !planet.is_gas_giant
+        && planet.interior.mantle_mass_fraction > 0.0
+        && heat_flux >= 0.04
+        && (planet.hydrosphere > 0.0 || heat_flux > 0.15)
+}
+
+/// Rocky planets sustain dynamo while mantle convection removes heat from the core fast enough. Gas and ice giants have convecting metallic hydrogen or ionic water layers.
+pub fn dynamo(planet: &Planetesimal) -> bool {
+    if planet.is_gas_giant {
+        return true;
+    }
+    let Interior {
+        core_mass_fraction,
+        surface_heat_flux,
+        ..
+    } = planet.interior;
+    core_mass_fraction > 0.05 && surface_heat_flux > 0.04
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earth_radiogenic_heat() {
+        let earth_mantle_kg = 4.0e24;
+        let heat = radiogenic_heat(&earth_mantle_kg, &EARTH_AGE);
+        assert!(heat > 19.0 && heat < 21.0);
+        let young = radiogenic_heat(&earth_mantle_kg, &1.0e8);
+        assert!(young > 3.0 * heat);
+    }
+
+    #[test]
+    fn io_tidal_heat() {
+        let jupiter_mass = JUPITER_MASS_IN_EARTH_MASSES / EARTH_MASSES_PER_SOLAR_MASS;
+        let io_a = 421700.0 / KM_PER_AU;
+        let heat = tidal_heat(&jupiter_mass, &io_a, &0.0041, &1821.6, &false);
+        assert!(heat > 50.0 && heat < 200.0);
+    }
+
+    #[test]
+    fn earth_central_pressure() {
+        let earth_mass = EARTH_MASS_IN_GRAMS / SOLAR_MASS_IN_GRAMS;
+        let pressure = central_pressure(&earth_mass, &EARTH_RADIUS_IN_KM, &0.32, &false);
+        assert!(pressure > 300.0 && pressure < 420.0);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::consts::*;
+use crate::structs::reservoir::SmallBodyPopulation;
+use crate::structs::Planetesimal;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Point in the frame co-rotating with the body, host at the origin and body on positive x axis.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LagrangePoint {
+    // units of AU
+    pub x: f64,
+    pub y: f64,
+    pub distance_to_host: f64,
+    pub distance_to_body: f64,
+}
+
+/// Equilibrium points of a body on circular orbit around its host, star for planets and planet for moons.
+/// L1 and L2 lie on the line through both bodies inside and outside the orbit, L3 on the opposite side of the host, L4 leads and L5 trails the body by 60°.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LagrangePoints {
+    pub l1: LagrangePoint,
+    pub l2: LagrangePoint,
+    pub l3: LagrangePoint,
+    pub l4: LagrangePoint,
+    pub l5: LagrangePoint,
+}
+
+/// Trojan asteroids librating around L4 or L5 point of a planet.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrojanSwarm {
+    // L4 swarm leads the planet, L5 trails it
+    pub leading: bool,
+    pub point: LagrangePoint,
+    pub population: SmallBodyPopulation,
+}
+
+/// Trojans larger than 1 km in both swarms of Jupiter.
+/// [Yoshida & Nakamura 2005, Size distribution of faint Jovian L4 Trojan asteroids](https://doi.org/10.1086/491508)
+const JUPITER_TROJANS: f64 = 1.0e6;
+
+/// Hill sphere of Jupiter, AU.
+const JUPITER_HILL_SPHERE_AU: f64 = 0.338;
+
+/// Lightest planet (Earth masses) holding a notable Trojan swarm, as Neptune.
+const TROJAN_HOST_MIN_EARTH_MASSES: f64 = 10.0;
+
+impl LagrangePoint {
+    fn new(x: f64, y: f64, a: &f64) -> Self {
+        Self {
+            x,
+            y,
+            distance_to_host: (x.powf(2.0) + y.powf(2.0)).sqrt(),
+            distance_to_body: ((x - a).powf(2.0) + y.powf(2.0)).sqrt(),
+        }
+    }
+}
+
+/// Positions of L1-L5 for body of given mass on orbit with semi-major axis a (AU) around host, masses in solar masses.
+/// Collinear points use series expansion in mass ratio, accurate while the body is much lighter than its host.
+pub fn lagrange_points(host_mass: &f64, mass: &f64, a: &f64) -> LagrangePoints {
+    let mass_ratio = mass / (host_mass + mass);
+    let hill_radius = a * (mass_ratio / 3.0).powf(1.0 / 3.0);
+    let triangular_y = a * 3.0_f64.sqrt() / 2.0;
+
+    LagrangePoints {
+        l1: LagrangePoint::new(a - hill_radius, 0.0, a),
+        l2: LagrangePoint::new(a + hill_radius, 0.0, a),
+        l3: LagrangePoint::new(-a * (1.0 + 5.0 * mass_ratio / 12.0), 0.0, a),
+        l4: LagrangePoint::new(a / 2.0, triangular_y, a),
+        l5: LagrangePoint::new(a / 2.0, -triangular_y, a),
+    }
+}
+
+/// Swarms at L4 and L5 of a massive planet with derived environment. Number of Trojans is scaled from Jupiter by planet mass and Hill sphere, leading swarm is usually richer as for Jupiter.
+/// Tadpole orbits spread radially by a * sqrt(8/3 * mass ratio) and cover about 60° of longitude.
+pub fn trojan_swarms(
+    planet: &Planetesimal,
+    stellar_mass: &f64,
+    rng: &mut dyn RngCore,
+) -> Vec<TrojanSwarm> {
+    let Planetesimal {
+        a,
+        mass,
+        earth_masses,
+        hill_sphere,
+        lagrange_points,
+        ..
+    } = planet;
+    if *earth_masses < TROJAN_HOST_MIN_EARTH_MASSES {
+        return Vec::new();
+    }
+    let number = JUPITER_TROJANS
+        * (earth_masses / JUPITER_MASS_IN_EARTH_MASSES)
+        * (hill_sphere / JUPITER_HILL_SPHERE_AU);
+    let leading_share = rng.gen_range(0.5..0.65);
+    let half_width = a * (8.0 / 3.0 * mass / (stellar_mass + mass)).sqrt();
+    // About 15° of inclination spread
+    let volume = 2.0 * half_width * (a * PI / 3.0) * (0.5 * a);
+
+    [
+        (true, lagrange_points.l4, leading_share),
+        (false, lagrange_points.l5, 1.0 - leading_share),
+    ]
+    .iter()
+    .map(|(leading, point, share)| TrojanSwarm {
+        leading: *leading,
+        point: *point,
+        population: SmallBodyPopulation::from_number(
+            a - half_width,
+            a + half_width,
+            number * share,
+            1.0,
+            volume,
+        ),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earth_lagrange_points() {
+        let earth_mass = EARTH_MASS_IN_GRAMS / SOLAR_MASS_IN_GRAMS;
+        let points = lagrange_points(&1.0, &earth_mass, &1.0);
+        // SOHO and JWST sit about 1.5 million km from Earth
+        let l1_km = points.l1.distance_to_body * KM_PER_AU;
+        let l2_km = points.l2.distance_to_body * KM_PER_AU;
+        assert!(l1_km > 1.4e6 && l1_km < 1.6e6);
+        assert!((l1_km - l2_km).abs() < 1.0);
+        assert!((points.l4.distance_to_host - 1.0).abs() < 1.0e-9);
+        assert!((points.l5.distance_to_body - 1.0).abs() < 1.0e-9);
+        assert!(points.l3.x < -1.0);
+    }
+}
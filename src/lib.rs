@@ -9,17 +9,36 @@
 )]
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub mod accrete;
+pub mod activity;
+pub mod catalogue;
+pub mod climate;
 pub mod consts;
 pub mod enviro;
+pub mod escape;
 
 #[cfg(events_log)]
 pub mod events_log;
+pub mod habitability;
+pub mod habitable_zone;
+pub mod interior;
+pub mod lagrange;
+pub mod magnetosphere;
+pub mod obliquity;
+pub mod observables;
+pub mod seasons;
+pub mod sky;
+pub mod solar_system;
+pub mod spectrum;
 pub mod structs;
+pub mod structure;
+pub mod tides;
 pub mod utils;
 pub mod wasm;
 
 pub use crate::accrete::Accrete;
+pub use structs::Composition;
 pub use structs::DustBand;
+pub use structs::PinnedPlanet;
 pub use structs::Planetesimal;
 pub use structs::PrimaryStar;
 pub use structs::Ring;
@@ -52,8 +71,7 @@ mod tests {
             write_to_file(&format!("{:?}", accrete.planetary_system()), path)
                 .expect("Failed to write fixture");
         }
-        let fixture = read_file(path);
-        fixture
+        read_file(path)
     }
 
     #[test]
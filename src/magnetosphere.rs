@@ -0,0 +1,108 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::Planetesimal;
+
+use serde::{Deserialize, Serialize};
+
+/// Intrinsic magnetic field of a planet and its interaction with stellar wind.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Magnetosphere {
+    // dipole moment in units of Earth's dipole moment (8.0e22 A m2)
+    pub dipole_moment: f64,
+    // field strength at magnetic equator on the surface, gauss
+    pub surface_field: f64,
+    // stellar wind dynamic pressure at the planet relative to solar wind at Earth
+    pub stellar_wind_pressure: f64,
+    // distance to the magnetopause on the day side, planetary radii
+    pub standoff_radius: f64,
+    // stellar wind reaches upper atmosphere and erodes it
+    pub atmospheric_stripping: bool,
+}
+
+impl Magnetosphere {
+    /// Field of a planet with already derived interior and rotation. Stellar wind is dynamic pressure at 1 AU relative to the Sun.
+    pub fn new(planet: &Planetesimal, stellar_wind: &f64) -> Self {
+        let dipole_moment = dipole_moment(planet);
+        let radius_ratio = planet.radius / EARTH_RADIUS_IN_KM;
+        let surface_field = EARTH_SURFACE_FIELD * dipole_moment / radius_ratio.powf(3.0);
+        let stellar_wind_pressure = stellar_wind / planet.distance_to_primary_star.powf(2.0);
+        let standoff_radius = standoff_radius(&surface_field, &stellar_wind_pressure);
+        // Magnetopause pressed down to ionosphere heights leaves atmosphere exposed to wind stronger than gravity can hold
+        let surface_grav = gravity(&acceleration(&planet.mass, &planet.radius));
+        let atmospheric_stripping = !planet.is_gas_giant
+            && standoff_radius < 2.0
+            && stellar_wind_pressure > surface_grav.powf(2.0);
+
+        Self {
+            dipole_moment,
+            surface_field,
+            stellar_wind_pressure,
+            standoff_radius,
+            atmospheric_stripping,
+        }
+    }
+
+    /// Share of volatiles that survives stellar wind erosion. Unprotected planets keep more of their atmosphere with stronger gravity and weaker wind, as Venus versus Mars.
+    pub fn atmospheric_retention(&self, surface_grav: &f64) -> f64 {
+        if !self.atmospheric_stripping {
+            return 1.0;
+        }
+        (surface_grav.powf(2.0) / self.stellar_wind_pressure).min(1.0)
+    }
+}
+
+/// Dipole moment relative to Earth, from magnetostrophic scaling: moment grows with cube of dynamo region radius and rotation rate, and with the cube root of heat flow driving convection in rocky cores.
+/// [Stevens 2005, Magnetospheric radio emission from extrasolar giant planets](https://arxiv.org/abs/astro-ph/0411185)
+/// [Olson & Christensen 2006, Dipole moment scaling for convection-driven planetary dynamos](https://doi.org/10.1016/j.epsl.2006.08.008)
+pub fn dipole_moment(planet: &Planetesimal) -> f64 {
+    let Planetesimal {
+        radius,
+        day_hours,
+        interior,
+        is_gas_giant,
+        mass,
+        ..
+    } = planet;
+    if !interior.dynamo || *day_hours <= 0.0 {
+        return 0.0;
+    }
+    let earth_masses = mass * EARTH_MASSES_PER_SOLAR_MASS;
+    let (dynamo_radius, convection) = match is_gas_giant {
+        // metallic hydrogen in Jupiter reaches 0.8 radius, ionic water in ice giants about half
+        true => (
+            radius * (0.5 + 0.3 * (earth_masses / JUPITER_MASS_IN_EARTH_MASSES).min(1.0)),
+            1.0,
+        ),
+        false => (
+            radius * interior.core_mass_fraction.sqrt(),
+            (interior.surface_heat_flux / EARTH_HEAT_FLUX).powf(1.0 / 3.0),
+        ),
+    };
+    let rotation = 24.0 / day_hours;
+    (dynamo_radius / EARTH_CORE_RADIUS_IN_KM).powf(3.0) * rotation * convection
+}
+
+/// Chapman-Ferraro standoff distance in planetary radii, where field pressure balances stellar wind. Earth's magnetopause sits at about 10 radii.
+pub fn standoff_radius(surface_field: &f64, stellar_wind_pressure: &f64) -> f64 {
+    if *stellar_wind_pressure <= 0.0 {
+        return INCREDIBLY_LARGE_NUMBER;
+    }
+    let standoff = EARTH_MAGNETOPAUSE_RADII
+        * (surface_field / EARTH_SURFACE_FIELD).powf(1.0 / 3.0)
+        * stellar_wind_pressure.powf(-1.0 / 6.0);
+    // Without field the wind is held off by ionosphere
+    standoff.max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn earth_standoff_radius() {
+        let standoff = standoff_radius(&EARTH_SURFACE_FIELD, &1.0);
+        assert!((standoff - EARTH_MAGNETOPAUSE_RADII).abs() < 1.0e-9);
+        assert_eq!(standoff_radius(&0.0, &1.0), 1.0);
+        assert!(standoff_radius(&EARTH_SURFACE_FIELD, &100.0) < standoff);
+    }
+}
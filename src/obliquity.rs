@@ -0,0 +1,128 @@
+use crate::structs::Planetesimal;
+use crate::utils::*;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Spread of primordial tilt of bodies grown by accretion of many small planetesimals, degrees.
+const QUIET_ACCRETION_TILT: f64 = 15.0;
+
+/// Share of bodies hit by giant impact in the last stage of growth, which accretion merges do not resolve.
+/// [Kokubo & Ida 2007, Formation of terrestrial planets from protoplanets. II. Statistics of planetary spin](https://doi.org/10.1086/521220)
+const LATE_GIANT_IMPACT_PROBABILITY: f64 = 0.2;
+
+/// Residual tilt of synchronously rotating bodies damped by tides into Cassini state, degrees.
+const CASSINI_STATE_TILT: f64 = 1.0;
+
+/// Lightest moon (fraction of planet mass) which stabilizes tilt of its planet, Moon is 0.0123 of Earth.
+/// [Laskar, Joutel & Robutel 1993, Stabilization of the Earth's obliquity by the Moon](https://doi.org/10.1038/361615a0)
+const STABILIZING_MOON_MASS_RATIO: f64 = 0.005;
+
+/// Regular oscillation of stabilized tilt around its mean, as Earth's 22.1-24.5°.
+const REGULAR_VARIATION: f64 = 1.3;
+
+/// Prograde tilts (degrees) which the solid planet without massive moon wanders through chaotically, 0-60° for Mars.
+/// [Laskar & Robutel 1993, The chaotic obliquity of the planets](https://doi.org/10.1038/361608a0)
+const CHAOTIC_ZONE: f64 = 60.0;
+
+/// Tilt of spin axis and its long-term variation.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Obliquity {
+    // units of degrees, above 90 for retrograde rotation
+    pub axial_tilt: f64,
+    pub is_retrograde: bool,
+    // massive moon keeps the tilt regular
+    pub is_stabilized_by_moon: bool,
+    // tilt wanders chaotically through the range driven by secular resonances
+    pub is_chaotic: bool,
+    // lowest and highest tilt over millions of years, degrees
+    pub variation: (f64, f64),
+}
+
+impl Obliquity {
+    /// Tilt of a body with derived tides and moons. Bodies which collided during accretion or met late giant impact have isotropic spin axes and rotate retrograde in half of cases, bodies grown by quiet accretion stay near upright.
+    /// Synchronous rotators settle upright. Massive moon or gas giant structure keeps tilt regular, solid planets without them wander chaotically at prograde tilts.
+    /// Known bodies of presets keep their measured tilt.
+    pub fn new(planet: &Planetesimal, rng: &mut dyn RngCore) -> Self {
+        let Planetesimal {
+            mass,
+            is_gas_giant,
+            is_moon,
+            has_collision,
+            moons,
+            tides,
+            is_known,
+            axial_tilt,
+            ..
+        } = planet;
+        let is_synchronous = tides.resonance == Some((1, 1));
+        let axial_tilt = match is_known {
+            true => *axial_tilt,
+            false => random_tilt(&is_synchronous, has_collision, rng),
+        };
+        let is_stabilized_by_moon = !is_moon
+            && moons
+                .iter()
+                .any(|moon| moon.mass >= STABILIZING_MOON_MASS_RATIO * mass);
+        let is_chaotic = !is_gas_giant
+            && !is_moon
+            && !is_synchronous
+            && !is_stabilized_by_moon
+            && axial_tilt < CHAOTIC_ZONE;
+        let variation = match is_chaotic {
+            true => (0.0, CHAOTIC_ZONE),
+            false => (
+                (axial_tilt - REGULAR_VARIATION).max(0.0),
+                (axial_tilt + REGULAR_VARIATION).min(180.0),
+            ),
+        };
+
+        Self {
+            axial_tilt: float_to_precision(axial_tilt),
+            is_retrograde: axial_tilt > 90.0,
+            is_stabilized_by_moon,
+            is_chaotic,
+            variation: (
+                float_to_precision(variation.0),
+                float_to_precision(variation.1),
+            ),
+        }
+    }
+}
+
+/// Tilt in degrees of body without measured one, upright for synchronous rotators, isotropic after giant impact.
+fn random_tilt(is_synchronous: &bool, has_collision: &bool, rng: &mut dyn RngCore) -> f64 {
+    let giant_impact = *has_collision || rng.gen_bool(LATE_GIANT_IMPACT_PROBABILITY);
+    match (is_synchronous, giant_impact) {
+        (true, _) => rng.gen_range(0.0..CASSINI_STATE_TILT),
+        // isotropic spin axis, uniform in cosine of tilt
+        (false, true) => rng.gen_range(-1.0_f64..1.0).acos().to_degrees(),
+        (false, false) => gaussian(0.0, QUIET_ACCRETION_TILT, rng).abs().min(90.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consts::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn moon_stabilizes_tilt() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut earth = Planetesimal::on_orbit(1.0, 0.0167, "earth".to_string());
+        earth.mass = EARTH_MASS_IN_GRAMS / SOLAR_MASS_IN_GRAMS;
+        let free = Obliquity::new(&earth, &mut rng);
+        assert!(free.is_chaotic && free.variation == (0.0, CHAOTIC_ZONE));
+
+        let mut moon = Planetesimal::on_orbit(1.0, 0.0167, "moon".to_string());
+        moon.mass = earth.mass * 0.0123;
+        earth.moons.push(moon);
+        earth.has_collision = true;
+        let stable = Obliquity::new(&earth, &mut rng);
+        assert!(stable.is_stabilized_by_moon && !stable.is_chaotic);
+        assert!(stable.variation.1 - stable.variation.0 <= 2.0 * REGULAR_VARIATION + 1.0e-9);
+        assert_eq!(stable.is_retrograde, stable.axial_tilt > 90.0);
+    }
+}
@@ -0,0 +1,373 @@
+use crate::consts::*;
+use crate::structs::{Planetesimal, PrimaryStar, System};
+use crate::utils::*;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Direction from which distant observer sees the system.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Observer {
+    // angle between line of sight and normal to the plane of orbits, degrees, 90 for edge-on view
+    pub inclination: f64,
+    // direction of observer projected to the plane of orbits, degrees
+    pub longitude: f64,
+}
+
+impl Default for Observer {
+    fn default() -> Self {
+        Self {
+            inclination: 90.0,
+            longitude: 0.0,
+        }
+    }
+}
+
+impl Observer {
+    /// Unit vector from the system to observer, plane of orbits is x-y.
+    fn line_of_sight(&self) -> [f64; 3] {
+        let (inclination, longitude) = (self.inclination.to_radians(), self.longitude.to_radians());
+        [
+            inclination.sin() * longitude.cos(),
+            inclination.sin() * longitude.sin(),
+            inclination.cos(),
+        ]
+    }
+}
+
+/// Sampling of synthetic time series.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Sampling {
+    // days from epoch
+    pub start: f64,
+    // units of days
+    pub duration: f64,
+    // time between samples, days
+    pub cadence: f64,
+    // standard deviation of relative flux
+    pub flux_noise: f64,
+    // standard deviation of radial velocity, m/s
+    pub rv_noise: f64,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            start: 0.0,
+            duration: DAYS_IN_A_YEAR,
+            cadence: 0.5 / 24.0,
+            flux_noise: 1.0e-4,
+            rv_noise: 1.0,
+        }
+    }
+}
+
+/// Transit and radial-velocity signal of one planet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PlanetSignal {
+    pub id: String,
+    // chance that randomly oriented observer sees transits
+    pub transit_probability: f64,
+    pub is_transiting: bool,
+    // sky-projected distance of planet from stellar center at conjunction, stellar radii
+    pub impact_parameter: f64,
+    // relative loss of flux without limb darkening
+    pub transit_depth: f64,
+    // first to last contact, hours
+    pub transit_duration: f64,
+    // first conjunction with observer after epoch, days
+    pub conjunction_time: f64,
+    // stellar reflex velocity, m/s
+    pub rv_semi_amplitude: f64,
+}
+
+/// Signals of all planets for one observer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Observables {
+    pub observer: Observer,
+    pub planets: Vec<PlanetSignal>,
+}
+
+impl Observables {
+    /// Transit geometry and reflex motion of planets on their Keplerian orbits, limb darkening and planet-planet interaction are neglected.
+    /// [Winn 2010, Transits and occultations](https://arxiv.org/abs/1001.2010)
+    pub fn new(system: &System, observer: &Observer) -> Self {
+        let PrimaryStar {
+            stellar_mass,
+            stellar_radius_au,
+            ..
+        } = system.primary_star;
+        let planets = system
+            .planets
+            .iter()
+            .map(|planet| {
+                let Planetesimal {
+                    id,
+                    a,
+                    e,
+                    mass,
+                    radius,
+                    orbital_period_days,
+                    longitude_of_periapsis,
+                    mean_anomaly,
+                    ..
+                } = planet;
+                let radius_ratio = radius / KM_PER_AU / stellar_radius_au;
+                // planet crosses line of sight at true longitude of observer
+                let conjunction_anomaly =
+                    (observer.longitude - longitude_of_periapsis).to_radians();
+                let conjunction_distance =
+                    a * (1.0 - e.powf(2.0)) / (1.0 + e * conjunction_anomaly.cos());
+                let impact_parameter = conjunction_distance
+                    * observer.inclination.to_radians().cos().abs()
+                    / stellar_radius_au;
+                let is_transiting = impact_parameter < 1.0 + radius_ratio;
+                let transit_duration = match is_transiting {
+                    true => {
+                        let chord =
+                            ((1.0 + radius_ratio).powf(2.0) - impact_parameter.powf(2.0)).sqrt();
+                        let angle = (stellar_radius_au * chord
+                            / conjunction_distance
+                            / observer.inclination.to_radians().sin())
+                        .min(1.0)
+                        .asin();
+                        orbital_period_days / PI * angle * conjunction_distance
+                            / (a * (1.0 - e.powf(2.0)).sqrt())
+                            * 24.0
+                    }
+                    false => 0.0,
+                };
+                let conjunction_mean_anomaly = mean_anomaly_at(&conjunction_anomaly, e);
+                let conjunction_time = ((conjunction_mean_anomaly - mean_anomaly.to_radians())
+                    / (2.0 * PI))
+                    .rem_euclid(1.0)
+                    * orbital_period_days;
+                let orbital_speed =
+                    2.0 * PI * a / (orbital_period_days * (1.0 - e.powf(2.0)).sqrt()) * M_PER_AU
+                        / (SECONDS_PER_HOUR * 24.0);
+
+                PlanetSignal {
+                    id: id.clone(),
+                    transit_probability: float_to_precision(
+                        ((stellar_radius_au * (1.0 + radius_ratio)) / conjunction_distance)
+                            .min(1.0),
+                    ),
+                    is_transiting,
+                    impact_parameter: float_to_precision(impact_parameter),
+                    transit_depth: float_to_precision(match is_transiting {
+                        true => overlap_area(&1.0, &radius_ratio, &impact_parameter) / PI,
+                        false => 0.0,
+                    }),
+                    transit_duration: float_to_precision(transit_duration),
+                    conjunction_time: float_to_precision(conjunction_time),
+                    rv_semi_amplitude: float_to_precision(
+                        mass / (stellar_mass + mass)
+                            * orbital_speed
+                            * observer.inclination.to_radians().sin().abs(),
+                    ),
+                }
+            })
+            .collect();
+
+        Self {
+            observer: *observer,
+            planets,
+        }
+    }
+}
+
+/// Relative flux of the star over time in days, dimmed by transiting planets, with Gaussian noise.
+pub fn light_curve(
+    system: &System,
+    observer: &Observer,
+    sampling: &Sampling,
+    rng: &mut dyn RngCore,
+) -> Vec<(f64, f64)> {
+    let stellar_radius = system.primary_star.stellar_radius_au;
+    let line_of_sight = observer.line_of_sight();
+    sample_times(sampling)
+        .iter()
+        .map(|time| {
+            let blocked: f64 = system
+                .planets
+                .iter()
+                .map(|planet| {
+                    let (x, y) = orbit_position(planet, time);
+                    let towards_observer = x * line_of_sight[0] + y * line_of_sight[1];
+                    if towards_observer <= 0.0 {
+                        return 0.0;
+                    }
+                    let separation = (x.powf(2.0) + y.powf(2.0) - towards_observer.powf(2.0))
+                        .max(0.0)
+                        .sqrt();
+                    overlap_area(
+                        &1.0,
+                        &(planet.radius / KM_PER_AU / stellar_radius),
+                        &(separation / stellar_radius),
+                    )
+                })
+                .sum::<f64>()
+                / PI;
+            let flux = 1.0 - blocked.min(1.0) + gaussian(0.0, sampling.flux_noise, rng);
+            (float_to_precision(*time), float_to_precision(flux))
+        })
+        .collect()
+}
+
+/// Radial velocity of the star in m/s over time in days, positive away from observer, with Gaussian noise.
+pub fn radial_velocity_curve(
+    system: &System,
+    observer: &Observer,
+    sampling: &Sampling,
+    rng: &mut dyn RngCore,
+) -> Vec<(f64, f64)> {
+    let stellar_mass = system.primary_star.stellar_mass;
+    let line_of_sight = observer.line_of_sight();
+    let meters_per_second = M_PER_AU / (SECONDS_PER_HOUR * 24.0);
+    sample_times(sampling)
+        .iter()
+        .map(|time| {
+            let velocity: f64 = system
+                .planets
+                .iter()
+                .map(|planet| {
+                    let Planetesimal {
+                        a,
+                        e,
+                        mass,
+                        orbital_period_days,
+                        longitude_of_periapsis,
+                        mean_anomaly,
+                        ..
+                    } = planet;
+                    let (vx, vy) = velocity_on_orbit(
+                        a,
+                        e,
+                        orbital_period_days,
+                        longitude_of_periapsis,
+                        mean_anomaly,
+                        time,
+                    );
+                    // star recedes while planet approaches observer
+                    -mass / (stellar_mass + mass)
+                        * (vx * line_of_sight[0] + vy * line_of_sight[1])
+                        * meters_per_second
+                })
+                .sum();
+            let rv = velocity + gaussian(0.0, sampling.rv_noise, rng);
+            (float_to_precision(*time), float_to_precision(rv))
+        })
+        .collect()
+}
+
+/// Overlap of two disks with radii at distance between their centers, in the same units.
+pub fn overlap_area(radius: &f64, other_radius: &f64, distance: &f64) -> f64 {
+    let (r1, r2, d) = (*radius, *other_radius, *distance);
+    if d >= r1 + r2 {
+        return 0.0;
+    }
+    if d <= (r1 - r2).abs() {
+        return PI * r1.min(r2).powf(2.0);
+    }
+    let angle1 = ((d.powf(2.0) + r1.powf(2.0) - r2.powf(2.0)) / (2.0 * d * r1))
+        .clamp(-1.0, 1.0)
+        .acos();
+    let angle2 = ((d.powf(2.0) + r2.powf(2.0) - r1.powf(2.0)) / (2.0 * d * r2))
+        .clamp(-1.0, 1.0)
+        .acos();
+    let kite = ((-d + r1 + r2) * (d + r1 - r2) * (d - r1 + r2) * (d + r1 + r2))
+        .max(0.0)
+        .sqrt();
+    r1.powf(2.0) * angle1 + r2.powf(2.0) * angle2 - kite / 2.0
+}
+
+fn orbit_position(planet: &Planetesimal, time: &f64) -> (f64, f64) {
+    position_on_orbit(
+        &planet.a,
+        &planet.e,
+        &planet.orbital_period_days,
+        &planet.longitude_of_periapsis,
+        &planet.mean_anomaly,
+        time,
+    )
+}
+
+fn sample_times(sampling: &Sampling) -> Vec<f64> {
+    if sampling.cadence <= 0.0 || sampling.duration < 0.0 {
+        return Vec::new();
+    }
+    let samples = (sampling.duration / sampling.cadence) as usize + 1;
+    (0..samples)
+        .map(|i| sampling.start + i as f64 * sampling.cadence)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::Disk;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn earth_and_jupiter_seen_edge_on() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut system = System::set_initial_conditions(
+            1.0,
+            DUST_DENSITY_COEFF,
+            K,
+            ECCENTRICITY_COEFF,
+            B,
+            Disk::default(),
+            SOLAR_AGE,
+        );
+        for (a, earth_masses, radius, period) in [
+            (1.0, 1.0, EARTH_RADIUS_IN_KM, DAYS_IN_A_YEAR),
+            (5.2, 317.8, 71492.0, 4332.6),
+        ] {
+            let mut planet = Planetesimal::on_orbit(a, 0.0, format!("planet:{}", a));
+            (planet.radius, planet.orbital_period_days) = (radius, period);
+            planet.mass = earth_masses / EARTH_MASSES_PER_SOLAR_MASS;
+            system.planets.push(planet);
+        }
+        // Jupiter in quadrature, away from Earth's transit
+        system.planets[1].mean_anomaly = 90.0;
+
+        let edge_on = Observables::new(&system, &Observer::default());
+        let (earth, jupiter) = (&edge_on.planets[0], &edge_on.planets[1]);
+        assert!(earth.is_transiting && (earth.transit_depth - 7.5e-5).abs() < 1.0e-5);
+        assert!(jupiter.is_transiting && (jupiter.conjunction_time - 4332.6 * 0.75).abs() < 1.0);
+        assert!(earth.transit_duration > 12.0 && earth.transit_duration < 14.5);
+        assert!((earth.transit_probability - 0.0049).abs() < 0.0005);
+        assert!((earth.rv_semi_amplitude - 0.09).abs() < 0.01);
+        assert!((jupiter.rv_semi_amplitude - 12.5).abs() < 0.3);
+
+        let face_on = Observables::new(
+            &system,
+            &Observer {
+                inclination: 0.0,
+                longitude: 0.0,
+            },
+        );
+        assert!(face_on
+            .planets
+            .iter()
+            .all(|p| !p.is_transiting && p.rv_semi_amplitude == 0.0));
+
+        // Noiseless light curve dips by transit depth around conjunction
+        let sampling = Sampling {
+            start: earth.conjunction_time - 0.5,
+            duration: 1.0,
+            cadence: 0.01,
+            flux_noise: 0.0,
+            rv_noise: 0.0,
+        };
+        let curve = light_curve(&system, &Observer::default(), &sampling, &mut rng);
+        let deepest = curve.iter().map(|(_, flux)| *flux).fold(1.0, f64::min);
+        assert!((1.0 - deepest - earth.transit_depth).abs() < 1.0e-6);
+        let rv = radial_velocity_curve(&system, &Observer::default(), &sampling, &mut rng);
+        assert!(rv
+            .iter()
+            .all(|(_, v)| v.abs() <= earth.rv_semi_amplitude + jupiter.rv_semi_amplitude + 1.0e-6));
+    }
+}
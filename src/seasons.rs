@@ -0,0 +1,293 @@
+use crate::consts::*;
+use crate::structs::Planetesimal;
+use crate::utils::*;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Centers of latitude bands of seasonal curves, degrees.
+const LATITUDES: [f64; 6] = [-75.0, -45.0, -15.0, 15.0, 45.0, 75.0];
+
+/// Orbital phases per year of seasonal curves, one per month.
+const ORBITAL_PHASES: usize = 12;
+
+/// Course of temperature through the year at one latitude.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SeasonalTemperature {
+    // degrees, positive to the north
+    pub latitude: f64,
+    // daily mean insolation at each orbital phase, W/m2
+    pub insolation: Vec<f64>,
+    // units of Kelvin
+    pub temps_kelvin: Vec<f64>,
+}
+
+/// Calendar of a planet counted in days from northern spring equinox.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Seasons {
+    // angle along the orbit from northern spring equinox to perihelion, degrees
+    pub longitude_of_perihelion: f64,
+    pub perihelion_day: f64,
+    pub aphelion_day: f64,
+    // northern spring, summer, autumn and winter, days
+    pub season_lengths: [f64; 4],
+    // days of orbital phases of seasonal curves
+    pub phase_days: Vec<f64>,
+    // empty for bodies without solid or liquid surface temperature
+    pub temperature_curves: Vec<SeasonalTemperature>,
+}
+
+impl Seasons {
+    /// Seasons of a planet with derived environment, orientation of its spin axis to perihelion is random unless the planet is a known body of a preset.
+    /// Surface temperature is spread over latitudes by annual insolation, seasonal swing around it is damped by oceans and thick atmosphere and kept within planet's extreme temperatures.
+    pub fn new(planet: &Planetesimal, stellar_luminosity: &f64, rng: &mut dyn RngCore) -> Self {
+        let longitude_of_perihelion = match planet.is_known {
+            true => planet.seasons.longitude_of_perihelion,
+            false => rng.gen_range(0.0..360.0),
+        };
+        Self::on_orbit(
+            planet,
+            &planet.a,
+            &planet.e,
+            &planet.orbital_period_days,
+            &longitude_of_perihelion,
+            stellar_luminosity,
+        )
+    }
+
+    /// Moons share calendar of their planet, temperature curves follow moon's own tilt and surface.
+    pub fn of_moon(
+        moon: &Planetesimal,
+        planet_seasons: &Seasons,
+        planet_e: &f64,
+        planet_year_days: &f64,
+        stellar_luminosity: &f64,
+    ) -> Self {
+        Self::on_orbit(
+            moon,
+            &moon.distance_to_primary_star,
+            planet_e,
+            planet_year_days,
+            &planet_seasons.longitude_of_perihelion,
+            stellar_luminosity,
+        )
+    }
+
+    /// Seasons of body on heliocentric orbit with given semi-major axis (AU), eccentricity and year (days).
+    fn on_orbit(
+        body: &Planetesimal,
+        a: &f64,
+        e: &f64,
+        orbital_period_days: &f64,
+        longitude_of_perihelion: &f64,
+        stellar_luminosity: &f64,
+    ) -> Self {
+        let Planetesimal {
+            axial_tilt,
+            surface_temp_kelvin,
+            min_temp_kelvin,
+            max_temp_kelvin,
+            hydrosphere,
+            surface_pressure_bar,
+            is_gas_giant,
+            ..
+        } = body;
+        let longitude_of_perihelion = *longitude_of_perihelion;
+        let day_at = |solar_longitude: f64| {
+            float_to_precision(days_from_equinox(
+                &solar_longitude,
+                e,
+                &longitude_of_perihelion,
+                orbital_period_days,
+            ))
+        };
+        let season_starts: Vec<f64> = [0.0, 90.0, 180.0, 270.0]
+            .iter()
+            .map(|solar_longitude| day_at(*solar_longitude))
+            .chain([*orbital_period_days])
+            .collect();
+        let season_lengths = [
+            float_to_precision(season_starts[1] - season_starts[0]),
+            float_to_precision(season_starts[2] - season_starts[1]),
+            float_to_precision(season_starts[3] - season_starts[2]),
+            float_to_precision(season_starts[4] - season_starts[3]),
+        ];
+        let phase_days: Vec<f64> = (0..ORBITAL_PHASES)
+            .map(|phase| {
+                float_to_precision(phase as f64 * orbital_period_days / ORBITAL_PHASES as f64)
+            })
+            .collect();
+
+        let has_surface_temp = !is_gas_giant && *surface_temp_kelvin > 0.0;
+        let temperature_curves = match has_surface_temp {
+            true => {
+                let positions: Vec<(f64, f64)> = phase_days
+                    .iter()
+                    .map(|day| {
+                        orbital_position(day, orbital_period_days, e, &longitude_of_perihelion)
+                    })
+                    .collect();
+                let insolation_curves: Vec<Vec<f64>> = LATITUDES
+                    .iter()
+                    .map(|latitude| {
+                        positions
+                            .iter()
+                            .map(|(solar_longitude, distance)| {
+                                insolation(
+                                    stellar_luminosity,
+                                    &(a * distance),
+                                    axial_tilt,
+                                    latitude,
+                                    solar_longitude,
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let annual: Vec<f64> = insolation_curves
+                    .iter()
+                    .map(|curve| curve.iter().sum::<f64>() / curve.len() as f64)
+                    .collect();
+                let weights: Vec<f64> = LATITUDES
+                    .iter()
+                    .map(|latitude| latitude.to_radians().cos())
+                    .collect();
+                let global_annual = annual
+                    .iter()
+                    .zip(weights.iter())
+                    .map(|(q, w)| q * w)
+                    .sum::<f64>()
+                    / weights.iter().sum::<f64>();
+                // Oceans and thick atmosphere store heat and damp seasonal swing
+                let damping = 1.0 / (1.0 + 5.0 * hydrosphere + surface_pressure_bar);
+
+                LATITUDES
+                    .iter()
+                    .zip(insolation_curves.iter().zip(annual.iter()))
+                    .map(|(latitude, (curve, annual_insolation))| {
+                        let annual_temp =
+                            surface_temp_kelvin * (annual_insolation / global_annual).powf(0.25);
+                        let temps_kelvin = curve
+                            .iter()
+                            .map(|q| {
+                                let swing = (q / annual_insolation).powf(0.25) - 1.0;
+                                let temp = annual_temp * (1.0 + damping * swing);
+                                float_to_precision(temp.max(*min_temp_kelvin).min(*max_temp_kelvin))
+                            })
+                            .collect();
+                        SeasonalTemperature {
+                            latitude: *latitude,
+                            insolation: curve.iter().map(|q| float_to_precision(*q)).collect(),
+                            temps_kelvin,
+                        }
+                    })
+                    .collect()
+            }
+            false => Vec::new(),
+        };
+
+        Self {
+            longitude_of_perihelion: float_to_precision(longitude_of_perihelion),
+            perihelion_day: day_at(longitude_of_perihelion),
+            aphelion_day: day_at(longitude_of_perihelion + 180.0),
+            season_lengths,
+            phase_days,
+            temperature_curves,
+        }
+    }
+}
+
+/// Daily mean stellar flux (W/m2) at the top of atmosphere for latitude in degrees, distance to star in AU, axial tilt in degrees and solar longitude in radians counted from northern spring equinox.
+/// Includes polar day and night, when the star does not set or rise.
+pub fn insolation(
+    stellar_luminosity: &f64,
+    distance: &f64,
+    axial_tilt: &f64,
+    latitude: &f64,
+    solar_longitude: &f64,
+) -> f64 {
+    let flux = SOLAR_CONSTANT * stellar_luminosity / distance.powf(2.0);
+    let declination = (axial_tilt.to_radians().sin() * solar_longitude.sin()).asin();
+    let latitude = latitude.to_radians();
+    // hour angle of sunset
+    let sunset = (-latitude.tan() * declination.tan())
+        .clamp(-1.0, 1.0)
+        .acos();
+    flux / PI
+        * (sunset * latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * sunset.sin())
+}
+
+/// Solar longitude (radians) and distance to star in units of semi-major axis at given day after northern spring equinox.
+pub fn orbital_position(
+    day: &f64,
+    year: &f64,
+    e: &f64,
+    longitude_of_perihelion: &f64,
+) -> (f64, f64) {
+    let perihelion = longitude_of_perihelion.to_radians();
+    let equinox_mean_anomaly = mean_anomaly_at(&-perihelion, e);
+    let mean_anomaly = (equinox_mean_anomaly + 2.0 * PI * day / year).rem_euclid(2.0 * PI);
+    let eccentric_anomaly = eccentric_anomaly(&mean_anomaly, e);
+    let solar_longitude = true_anomaly(&eccentric_anomaly, e) + perihelion;
+    (solar_longitude, 1.0 - e * eccentric_anomaly.cos())
+}
+
+/// Days after northern spring equinox when planet reaches solar longitude in degrees.
+pub fn days_from_equinox(
+    solar_longitude: &f64,
+    e: &f64,
+    longitude_of_perihelion: &f64,
+    year: &f64,
+) -> f64 {
+    let perihelion = longitude_of_perihelion.to_radians();
+    let equinox_mean_anomaly = mean_anomaly_at(&-perihelion, e);
+    let mean_anomaly = mean_anomaly_at(&(solar_longitude.to_radians() - perihelion), e);
+    (mean_anomaly - equinox_mean_anomaly).rem_euclid(2.0 * PI) / (2.0 * PI) * year
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::System;
+    use crate::structure::Structure;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn earth_calendar() {
+        let (e, perihelion, year) = (0.0167, 283.0, 365.25);
+        let summer = days_from_equinox(&180.0, &e, &perihelion, &year)
+            - days_from_equinox(&90.0, &e, &perihelion, &year);
+        let winter = year - days_from_equinox(&270.0, &e, &perihelion, &year);
+        assert!(summer > 93.0 && summer < 94.0);
+        assert!(winter > 88.5 && winter < 89.5);
+        // Perihelion in early January, about 289 days after March equinox
+        let perihelion_day = days_from_equinox(&perihelion, &e, &perihelion, &year);
+        assert!((perihelion_day - 289.0).abs() < 3.0);
+    }
+
+    #[test]
+    fn polar_day_and_night() {
+        let summer_solstice = PI / 2.0;
+        let north_pole = insolation(&1.0, &1.0167, &23.44, &90.0, &summer_solstice);
+        let south_pole = insolation(&1.0, &1.0167, &23.44, &-90.0, &summer_solstice);
+        assert!(north_pole > 500.0 && north_pole < 550.0);
+        assert!(south_pole.abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn moon_follows_planet_calendar() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut sol = System::sol();
+        sol.process_planets(&true, &Structure::default(), &mut rng);
+        let earth = &sol.planets[2];
+        let moon = &earth.moons[0].seasons;
+        assert_eq!(
+            moon.longitude_of_perihelion,
+            earth.seasons.longitude_of_perihelion
+        );
+        assert_eq!(moon.season_lengths, earth.seasons.season_lengths);
+        assert_eq!(moon.phase_days, earth.seasons.phase_days);
+    }
+}
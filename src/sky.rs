@@ -0,0 +1,250 @@
+use crate::consts::*;
+use crate::spectrum::visual_share;
+use crate::structs::{Planetesimal, PrimaryStar, System};
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Geometric albedo of Lambertian sphere relative to its Bond albedo.
+const LAMBERT_GEOMETRIC_ALBEDO: f64 = 2.0 / 3.0;
+
+/// Planet or moon in the sky of another body.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SkyObject {
+    pub id: String,
+    pub is_moon: bool,
+    // direction in the common plane of orbits, degrees
+    pub longitude: f64,
+    // angular distance from the star, degrees
+    pub elongation: f64,
+    // units of AU
+    pub distance: f64,
+    // units of degrees
+    pub angular_diameter: f64,
+    // angle between the star and observer seen from the object, degrees
+    pub phase_angle: f64,
+    // lit share of the disk
+    pub illuminated_fraction: f64,
+    pub apparent_magnitude: f64,
+}
+
+/// Sky seen from a planet or moon at given time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Sky {
+    // days from epoch
+    pub time: f64,
+    // direction of the star in the common plane of orbits, degrees
+    pub star_longitude: f64,
+    // units of AU
+    pub star_distance: f64,
+    // units of degrees
+    pub star_angular_diameter: f64,
+    pub star_apparent_magnitude: f64,
+    // all other planets and moons, including host planet of a moon
+    pub objects: Vec<SkyObject>,
+}
+
+impl Sky {
+    /// Sky from body with given id at time in days from epoch, none if system has no such body.
+    /// Bodies move on coplanar Keplerian orbits from their phases at epoch, moons around their moving planets.
+    pub fn new(system: &System, observer_id: &str, time: &f64) -> Option<Self> {
+        let positions = body_positions(system, time);
+        let (_, observer) = positions.iter().find(|(body, _)| body.id == observer_id)?;
+        let star_offset = (-observer.0, -observer.1);
+        let star_distance = distance(&star_offset);
+        let PrimaryStar {
+            stellar_radius_au, ..
+        } = system.primary_star;
+        let magnitude_at_au = star_magnitude_at_au(&system.primary_star);
+
+        let objects = positions
+            .iter()
+            .filter(|(body, _)| body.id != observer_id)
+            .map(|(body, position)| {
+                let offset = (position.0 - observer.0, position.1 - observer.1);
+                let object_distance = distance(&offset);
+                let heliocentric_distance = distance(position);
+                // star and observer seen from the object
+                let phase_angle =
+                    angle_between(&(-position.0, -position.1), &(-offset.0, -offset.1));
+                let radius_au = body.radius / KM_PER_AU;
+                let reflected = reflecting_albedo(body)
+                    * LAMBERT_GEOMETRIC_ALBEDO
+                    * radius_au.powf(2.0)
+                    * lambert_phase(&phase_angle)
+                    / (heliocentric_distance * object_distance).powf(2.0);
+                let apparent_magnitude = match reflected > 0.0 {
+                    true => magnitude_at_au - 2.5 * reflected.log10(),
+                    false => INCREDIBLY_LARGE_NUMBER,
+                };
+
+                SkyObject {
+                    id: body.id.clone(),
+                    is_moon: body.is_moon,
+                    longitude: float_to_precision(longitude(&offset)),
+                    elongation: float_to_precision(
+                        angle_between(&star_offset, &offset).to_degrees(),
+                    ),
+                    distance: float_to_precision(object_distance),
+                    angular_diameter: float_to_precision(angular_diameter(
+                        &radius_au,
+                        &object_distance,
+                    )),
+                    phase_angle: float_to_precision(phase_angle.to_degrees()),
+                    illuminated_fraction: float_to_precision((1.0 + phase_angle.cos()) / 2.0),
+                    apparent_magnitude: float_to_precision(apparent_magnitude),
+                }
+            })
+            .collect();
+
+        Some(Self {
+            time: *time,
+            star_longitude: float_to_precision(longitude(&star_offset)),
+            star_distance: float_to_precision(star_distance),
+            star_angular_diameter: float_to_precision(angular_diameter(
+                &stellar_radius_au,
+                &star_distance,
+            )),
+            star_apparent_magnitude: float_to_precision(
+                magnitude_at_au + 5.0 * star_distance.log10(),
+            ),
+            objects,
+        })
+    }
+}
+
+/// Planets and moons with their positions relative to the star at time in days from epoch, AU.
+pub fn body_positions<'a>(system: &'a System, time: &f64) -> Vec<(&'a Planetesimal, (f64, f64))> {
+    let orbit = |body: &Planetesimal| {
+        position_on_orbit(
+            &body.a,
+            &body.e,
+            &body.orbital_period_days,
+            &body.longitude_of_periapsis,
+            &body.mean_anomaly,
+            time,
+        )
+    };
+    let mut positions = Vec::new();
+    for planet in system.planets.iter() {
+        let planet_position = orbit(planet);
+        positions.push((planet, planet_position));
+        for moon in planet.moons.iter() {
+            let moon_offset = orbit(moon);
+            positions.push((
+                moon,
+                (
+                    planet_position.0 + moon_offset.0,
+                    planet_position.1 + moon_offset.1,
+                ),
+            ));
+        }
+    }
+    positions
+}
+
+/// Apparent visual magnitude of the star from 1 AU. Visual share of its blackbody relative to the Sun stands for bolometric correction.
+pub fn star_magnitude_at_au(primary_star: &PrimaryStar) -> f64 {
+    let PrimaryStar {
+        stellar_luminosity,
+        stellar_surface_temp,
+        ..
+    } = primary_star;
+    let visual_luminosity =
+        stellar_luminosity * visual_share(stellar_surface_temp) / visual_share(&SUN_EFFECTIVE_TEMP);
+    SUN_ABSOLUTE_MAGNITUDE - 2.5 * visual_luminosity.log10() + 5.0 * (1.0 / AU_PER_PARSEC).log10()
+        - 5.0
+}
+
+/// Angular diameter in degrees of a sphere with radius at distance, both in the same units.
+pub fn angular_diameter(radius: &f64, distance: &f64) -> f64 {
+    if *distance <= 0.0 {
+        return 180.0;
+    }
+    2.0 * (radius / distance).min(1.0).asin().to_degrees()
+}
+
+/// Brightness of Lambertian sphere at phase angle in radians relative to full phase.
+pub fn lambert_phase(phase_angle: &f64) -> f64 {
+    (phase_angle.sin() + (PI - phase_angle) * phase_angle.cos()) / PI
+}
+
+/// Bond albedo of body, bare rock or ice for airless bodies without derived climate.
+fn reflecting_albedo(body: &Planetesimal) -> f64 {
+    match (body.albedo > 0.0, body.composition.ice > 0.5) {
+        (true, _) => body.albedo,
+        (false, true) => AIRLESS_ICE_ALBEDO,
+        (false, false) => AIRLESS_ROCKY_ALBEDO,
+    }
+}
+
+fn distance(offset: &(f64, f64)) -> f64 {
+    offset.0.hypot(offset.1)
+}
+
+/// Direction of offset in degrees, between 0 and 360.
+fn longitude(offset: &(f64, f64)) -> f64 {
+    offset.1.atan2(offset.0).to_degrees().rem_euclid(360.0)
+}
+
+/// Angle between two directions in radians.
+fn angle_between(first: &(f64, f64), second: &(f64, f64)) -> f64 {
+    let lengths = distance(first) * distance(second);
+    if lengths <= 0.0 {
+        return 0.0;
+    }
+    ((first.0 * second.0 + first.1 * second.1) / lengths)
+        .clamp(-1.0, 1.0)
+        .acos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::Disk;
+
+    #[test]
+    fn earth_sky() {
+        let mut system = System::set_initial_conditions(
+            1.0,
+            DUST_DENSITY_COEFF,
+            K,
+            ECCENTRICITY_COEFF,
+            B,
+            Disk::default(),
+            SOLAR_AGE,
+        );
+        let body = |id: &str, a: f64, radius: f64, albedo: f64, period: f64| {
+            let mut body = Planetesimal::on_orbit(a, 0.0, id.to_string());
+            (body.radius, body.albedo, body.orbital_period_days) = (radius, albedo, period);
+            body
+        };
+        let mut earth = body("earth", 1.0, EARTH_RADIUS_IN_KM, 0.3, 365.25);
+        let mut moon = body("moon", 0.00257, 1737.4, 0.11, 27.32);
+        moon.is_moon = true;
+        earth.moons.push(moon);
+        system.planets.push(earth);
+        system
+            .planets
+            .push(body("jupiter", 5.2, 71492.0, 0.343, 4332.6));
+
+        // Jupiter in opposition, Moon full
+        let sky = Sky::new(&system, "earth", &0.0).unwrap();
+        assert!((sky.star_apparent_magnitude + 26.74).abs() < 0.1);
+        assert!(sky.star_angular_diameter > 0.5 && sky.star_angular_diameter < 0.6);
+        let jupiter = sky.objects.iter().find(|o| o.id == "jupiter").unwrap();
+        assert!(
+            (jupiter.elongation - 180.0).abs() < 1.0e-6 && jupiter.illuminated_fraction > 0.999
+        );
+        assert!(jupiter.apparent_magnitude > -3.0 && jupiter.apparent_magnitude < -1.5);
+        let moon = sky.objects.iter().find(|o| o.id == "moon").unwrap();
+        assert!(moon.angular_diameter > 0.5 && moon.angular_diameter < 0.55);
+        assert!(moon.apparent_magnitude < -12.0);
+
+        // New Earth seen from the Moon
+        let from_moon = Sky::new(&system, "moon", &0.0).unwrap();
+        let earth = from_moon.objects.iter().find(|o| o.id == "earth").unwrap();
+        assert!(earth.angular_diameter > 1.8 && earth.illuminated_fraction < 0.001);
+        assert!(Sky::new(&system, "pluto", &0.0).is_none());
+    }
+}
@@ -0,0 +1,569 @@
+use crate::consts::*;
+use crate::enviro::orbital_zone;
+use crate::structs::{Composition, Planetesimal, PrimaryStar, System};
+use crate::structure::StructureModel;
+use crate::utils::*;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Measured properties of a Solar System body.
+#[derive(Debug, Copy, Clone, Serialize, PartialEq)]
+pub struct KnownBody {
+    pub id: &'static str,
+    // planet orbited by a moon
+    pub host: Option<&'static str>,
+    // semi-major axis, AU, around host planet for moons
+    pub a: f64,
+    pub e: f64,
+    pub earth_masses: f64,
+    // equatorial radius, km
+    pub radius: f64,
+    // estimated bulk mass fractions
+    pub composition: Composition,
+    pub is_gas_giant: bool,
+    // captured moons are irregular
+    pub is_regular_moon: bool,
+    // mean surface temperature, Kelvin, none for giants without surface
+    pub surface_temp_kelvin: Option<f64>,
+    // mean surface pressure, bar, none for giants without surface
+    pub surface_pressure_bar: Option<f64>,
+    // sidereal rotation period, hours
+    pub day_hours: f64,
+    // units of g/cc
+    pub density: f64,
+    // tilt of spin axis to heliocentric orbit of the body or of its planet, degrees
+    pub axial_tilt: f64,
+    // angle along the orbit from northern spring equinox to perihelion, degrees, none for moons which follow calendar of their planet
+    pub longitude_of_perihelion: Option<f64>,
+}
+
+/// Chondritic rock with metal core of given mass fraction.
+const fn rocky(metal: f64) -> Composition {
+    Composition {
+        rock: 1.0 - metal,
+        metal,
+        ice: 0.0,
+        gas: 0.0,
+    }
+}
+
+/// Rock and metal in chondritic ratio with water ice of given mass fraction.
+const fn icy(ice: f64) -> Composition {
+    Composition {
+        rock: (1.0 - ice) * 0.7,
+        metal: (1.0 - ice) * 0.3,
+        ice,
+        gas: 0.0,
+    }
+}
+
+/// Hydrogen and helium envelope of given mass fraction over icy core.
+const fn giant(gas: f64) -> Composition {
+    Composition {
+        rock: (1.0 - gas) * 0.35,
+        metal: (1.0 - gas) * 0.15,
+        ice: (1.0 - gas) * 0.5,
+        gas,
+    }
+}
+
+/// The Sun's eight planets and their major moons, each moon after its planet. Trace exospheres of airless bodies are kept as their pressure.
+/// Tilts and perihelion longitudes follow from IAU spin axes and J2000 orbital elements, regular moons share equatorial plane of their planet.
+/// [NASA Planetary Fact Sheets](https://nssdc.gsfc.nasa.gov/planetary/planetfact.html)
+/// [JPL Planetary Satellite Physical Parameters](https://ssd.jpl.nasa.gov/sats/phys_par/)
+/// [Archinal et al. 2018, Report of the IAU Working Group on Cartographic Coordinates and Rotational Elements: 2015](https://doi.org/10.1007/s10569-017-9805-5)
+pub const SOLAR_SYSTEM: [KnownBody; 15] = [
+    KnownBody {
+        id: "mercury",
+        host: None,
+        a: 0.387098,
+        e: 0.205630,
+        earth_masses: 0.0553,
+        radius: 2439.7,
+        composition: rocky(0.7),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(440.0),
+        surface_pressure_bar: Some(5.0e-15),
+        day_hours: 1407.6,
+        density: 5.427,
+        axial_tilt: 0.034,
+        longitude_of_perihelion: Some(49.2),
+    },
+    KnownBody {
+        id: "venus",
+        host: None,
+        a: 0.723332,
+        e: 0.006772,
+        earth_masses: 0.815,
+        radius: 6051.8,
+        composition: rocky(0.31),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(737.0),
+        surface_pressure_bar: Some(92.0),
+        day_hours: 5832.5,
+        density: 5.243,
+        axial_tilt: 177.36,
+        longitude_of_perihelion: Some(73.8),
+    },
+    KnownBody {
+        id: "earth",
+        host: None,
+        a: 1.000001,
+        e: 0.016709,
+        earth_masses: 1.0,
+        radius: 6378.1,
+        composition: rocky(0.32),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(288.0),
+        surface_pressure_bar: Some(1.014),
+        day_hours: 23.9345,
+        density: 5.514,
+        axial_tilt: 23.44,
+        longitude_of_perihelion: Some(282.9),
+    },
+    KnownBody {
+        id: "moon",
+        host: Some("earth"),
+        a: 384399.0 / KM_PER_AU,
+        e: 0.0549,
+        earth_masses: 0.0123,
+        radius: 1737.4,
+        composition: rocky(0.02),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(250.0),
+        surface_pressure_bar: Some(3.0e-15),
+        day_hours: 655.72,
+        density: 3.344,
+        axial_tilt: 1.54,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "mars",
+        host: None,
+        a: 1.523679,
+        e: 0.0934,
+        earth_masses: 0.107,
+        radius: 3396.2,
+        composition: rocky(0.24),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(210.0),
+        surface_pressure_bar: Some(0.00636),
+        day_hours: 24.6229,
+        density: 3.934,
+        axial_tilt: 25.19,
+        longitude_of_perihelion: Some(251.0),
+    },
+    KnownBody {
+        id: "jupiter",
+        host: None,
+        a: 5.2044,
+        e: 0.0489,
+        earth_masses: 317.83,
+        radius: 71492.0,
+        composition: giant(0.9),
+        is_gas_giant: true,
+        is_regular_moon: false,
+        surface_temp_kelvin: None,
+        surface_pressure_bar: None,
+        day_hours: 9.925,
+        density: 1.326,
+        axial_tilt: 3.13,
+        longitude_of_perihelion: Some(57.5),
+    },
+    KnownBody {
+        id: "io",
+        host: Some("jupiter"),
+        a: 421700.0 / KM_PER_AU,
+        e: 0.0041,
+        earth_masses: 0.01496,
+        radius: 1821.6,
+        composition: rocky(0.2),
+        is_gas_giant: false,
+        is_regular_moon: true,
+        surface_temp_kelvin: Some(110.0),
+        surface_pressure_bar: Some(1.0e-9),
+        day_hours: 42.459,
+        density: 3.528,
+        axial_tilt: 3.12,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "europa",
+        host: Some("jupiter"),
+        a: 671034.0 / KM_PER_AU,
+        e: 0.009,
+        earth_masses: 0.00804,
+        radius: 1560.8,
+        composition: icy(0.08),
+        is_gas_giant: false,
+        is_regular_moon: true,
+        surface_temp_kelvin: Some(102.0),
+        surface_pressure_bar: Some(1.0e-12),
+        day_hours: 85.228,
+        density: 3.013,
+        axial_tilt: 3.1,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "ganymede",
+        host: Some("jupiter"),
+        a: 1070412.0 / KM_PER_AU,
+        e: 0.0013,
+        earth_masses: 0.02481,
+        radius: 2634.1,
+        composition: icy(0.45),
+        is_gas_giant: false,
+        is_regular_moon: true,
+        surface_temp_kelvin: Some(110.0),
+        surface_pressure_bar: Some(1.0e-12),
+        day_hours: 171.709,
+        density: 1.942,
+        axial_tilt: 3.02,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "callisto",
+        host: Some("jupiter"),
+        a: 1882709.0 / KM_PER_AU,
+        e: 0.0074,
+        earth_masses: 0.01802,
+        radius: 2410.3,
+        composition: icy(0.5),
+        is_gas_giant: false,
+        is_regular_moon: true,
+        surface_temp_kelvin: Some(134.0),
+        surface_pressure_bar: Some(7.5e-12),
+        day_hours: 400.536,
+        density: 1.834,
+        axial_tilt: 2.68,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "saturn",
+        host: None,
+        a: 9.5826,
+        e: 0.0565,
+        earth_masses: 95.16,
+        radius: 60268.0,
+        composition: giant(0.8),
+        is_gas_giant: true,
+        is_regular_moon: false,
+        surface_temp_kelvin: None,
+        surface_pressure_bar: None,
+        day_hours: 10.656,
+        density: 0.687,
+        axial_tilt: 26.73,
+        longitude_of_perihelion: Some(279.0),
+    },
+    KnownBody {
+        id: "titan",
+        host: Some("saturn"),
+        a: 1221870.0 / KM_PER_AU,
+        e: 0.0288,
+        earth_masses: 0.02253,
+        radius: 2574.7,
+        composition: icy(0.45),
+        is_gas_giant: false,
+        is_regular_moon: true,
+        surface_temp_kelvin: Some(94.0),
+        surface_pressure_bar: Some(1.467),
+        day_hours: 382.69,
+        density: 1.88,
+        axial_tilt: 26.72,
+        longitude_of_perihelion: None,
+    },
+    KnownBody {
+        id: "uranus",
+        host: None,
+        a: 19.19126,
+        e: 0.04717,
+        earth_masses: 14.54,
+        radius: 25559.0,
+        composition: giant(0.15),
+        is_gas_giant: true,
+        is_regular_moon: false,
+        surface_temp_kelvin: None,
+        surface_pressure_bar: None,
+        day_hours: 17.24,
+        density: 1.27,
+        axial_tilt: 97.77,
+        longitude_of_perihelion: Some(3.4),
+    },
+    KnownBody {
+        id: "neptune",
+        host: None,
+        a: 30.07,
+        e: 0.008678,
+        earth_masses: 17.15,
+        radius: 24764.0,
+        composition: giant(0.15),
+        is_gas_giant: true,
+        is_regular_moon: false,
+        surface_temp_kelvin: None,
+        surface_pressure_bar: None,
+        day_hours: 16.11,
+        density: 1.638,
+        axial_tilt: 28.32,
+        longitude_of_perihelion: Some(359.5),
+    },
+    KnownBody {
+        id: "triton",
+        host: Some("neptune"),
+        a: 354759.0 / KM_PER_AU,
+        e: 0.000016,
+        earth_masses: 0.00358,
+        radius: 1353.4,
+        composition: icy(0.3),
+        is_gas_giant: false,
+        is_regular_moon: false,
+        surface_temp_kelvin: Some(38.0),
+        surface_pressure_bar: Some(1.4e-5),
+        day_hours: 141.044,
+        density: 2.061,
+        axial_tilt: 129.6,
+        longitude_of_perihelion: None,
+    },
+];
+
+impl KnownBody {
+    /// Body with measured orbit, mass, size, composition, tilt and calendar, protected from change. Environment is left to be derived.
+    pub fn planetesimal(
+        &self,
+        primary_star: &PrimaryStar,
+        distance_to_primary_star: &f64,
+    ) -> Planetesimal {
+        let mut body = Planetesimal::on_orbit(self.a, self.e, self.id.to_string());
+        body.mass = self.earth_masses / EARTH_MASSES_PER_SOLAR_MASS;
+        body.radius = self.radius;
+        body.density = self.density;
+        body.day_hours = self.day_hours;
+        body.composition = self.composition;
+        body.is_gas_giant = self.is_gas_giant;
+        body.is_moon = self.host.is_some();
+        body.is_regular_moon = self.is_regular_moon;
+        body.distance_to_primary_star = *distance_to_primary_star;
+        body.is_protected = true;
+        body.is_known = true;
+        body.axial_tilt = self.axial_tilt;
+        if let Some(longitude_of_perihelion) = self.longitude_of_perihelion {
+            body.seasons.longitude_of_perihelion = longitude_of_perihelion;
+        }
+        body.orbit_zone = orbital_zone(&primary_star.stellar_luminosity, *distance_to_primary_star);
+        if !body.is_moon {
+            body.orbit_clearing =
+                clearing_neightbourhood(&body.mass, &body.a, &primary_star.stellar_mass);
+        }
+        body
+    }
+}
+
+/// Planets of the Solar System with their moons around given star.
+pub fn sol_planets(primary_star: &PrimaryStar) -> Vec<Planetesimal> {
+    SOLAR_SYSTEM
+        .iter()
+        .filter(|body| body.host.is_none())
+        .map(|known_planet| {
+            let mut planet = known_planet.planetesimal(primary_star, &known_planet.a);
+            planet.moons = SOLAR_SYSTEM
+                .iter()
+                .filter(|body| body.host == Some(known_planet.id))
+                .map(|known_moon| known_moon.planetesimal(primary_star, &known_planet.a))
+                .collect();
+            planet
+        })
+        .collect()
+}
+
+/// Derived value of a property against the measured one.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Deviation {
+    pub derived: f64,
+    pub actual: f64,
+    // (derived - actual) / actual
+    pub relative_error: f64,
+}
+
+impl Deviation {
+    pub fn new(derived: &f64, actual: &f64) -> Self {
+        Self {
+            derived: float_to_precision(*derived),
+            actual: *actual,
+            relative_error: float_to_precision((derived - actual) / actual),
+        }
+    }
+}
+
+/// Derived environment of one Solar System body against measurements.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BodyReport {
+    pub id: String,
+    pub is_moon: bool,
+    // none for giants without surface
+    pub surface_temp_kelvin: Option<Deviation>,
+    pub surface_pressure_bar: Option<Deviation>,
+    pub day_hours: Deviation,
+    pub density: Deviation,
+}
+
+/// Environment pipeline run on the Solar System preset, regression and calibration reference for environment models.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SolReport {
+    pub bodies: Vec<BodyReport>,
+    // median absolute relative error over bodies with the property
+    pub temperature_error: f64,
+    pub pressure_error: f64,
+    pub day_error: f64,
+    pub density_error: f64,
+}
+
+impl SolReport {
+    /// Derive environments of System::sol() and compare them with measured values.
+    pub fn new(
+        hydrodynamic_escape: &bool,
+        structure: &dyn StructureModel,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let mut system = System::sol();
+        system.process_planets(hydrodynamic_escape, structure, rng);
+        Self::compare(&system)
+    }
+
+    /// Compare bodies of a system with known bodies of the same id.
+    pub fn compare(system: &System) -> Self {
+        let bodies: Vec<BodyReport> = system
+            .planets
+            .iter()
+            .flat_map(|planet| std::iter::once(planet).chain(planet.moons.iter()))
+            .filter_map(|body| {
+                let known = SOLAR_SYSTEM.iter().find(|known| known.id == body.id)?;
+                Some(BodyReport {
+                    id: body.id.clone(),
+                    is_moon: body.is_moon,
+                    surface_temp_kelvin: known
+                        .surface_temp_kelvin
+                        .map(|actual| Deviation::new(&body.surface_temp_kelvin, &actual)),
+                    surface_pressure_bar: known
+                        .surface_pressure_bar
+                        .map(|actual| Deviation::new(&body.surface_pressure_bar, &actual)),
+                    day_hours: Deviation::new(&body.day_hours, &known.day_hours),
+                    density: Deviation::new(&body.density, &known.density),
+                })
+            })
+            .collect();
+        let median_error = |property: fn(&BodyReport) -> Option<Deviation>| {
+            let errors: Vec<f64> = bodies
+                .iter()
+                .filter_map(property)
+                .map(|deviation| deviation.relative_error.abs())
+                .collect();
+            float_to_precision(median(&errors))
+        };
+
+        Self {
+            temperature_error: median_error(|body| body.surface_temp_kelvin),
+            pressure_error: median_error(|body| body.surface_pressure_bar),
+            day_error: median_error(|body| Some(body.day_hours)),
+            density_error: median_error(|body| Some(body.density)),
+            bodies,
+        }
+    }
+}
+
+fn median(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    match sorted.len().is_multiple_of(2) {
+        true => (sorted[middle - 1] + sorted[middle]) / 2.0,
+        false => sorted[middle],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::Structure;
+    use crate::Accrete;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn sol_preset() {
+        let sol = System::sol();
+        let ids: Vec<&str> = sol.planets.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["mercury", "venus", "earth", "mars", "jupiter", "saturn", "uranus", "neptune"]
+        );
+        let moons: Vec<usize> = sol.planets.iter().map(|p| p.moons.len()).collect();
+        assert_eq!(moons, [0, 0, 1, 0, 4, 1, 0, 1]);
+        let earth = &sol.planets[2];
+        assert!((earth.mass * EARTH_MASSES_PER_SOLAR_MASS - 1.0).abs() < 1.0e-9);
+        assert!(earth.moons[0].is_moon && earth.moons[0].distance_to_primary_star == earth.a);
+        assert!(!sol.planets[7].moons[0].is_regular_moon);
+        assert!(sol
+            .planets
+            .iter()
+            .all(|p| p.is_protected && p.moons.iter().all(|m| m.is_protected)));
+        assert!(!sol.dust_left);
+        assert!(sol.primary_star.age == SOLAR_AGE && sol.primary_star.activity.age == SOLAR_AGE);
+    }
+
+    #[test]
+    fn sol_spin_does_not_depend_on_seed() {
+        let spins: Vec<Vec<(f64, f64)>> = [1, 2]
+            .iter()
+            .map(|seed| {
+                let mut sol = System::sol();
+                sol.process_planets(
+                    &true,
+                    &Structure::default(),
+                    &mut ChaCha8Rng::seed_from_u64(*seed),
+                );
+                sol.planets
+                    .iter()
+                    .flat_map(|planet| std::iter::once(planet).chain(planet.moons.iter()))
+                    .map(|body| (body.axial_tilt, body.seasons.longitude_of_perihelion))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(spins[0], spins[1]);
+        // Earth and the Moon share the calendar with perihelion in early January
+        assert_eq!(&spins[0][2..4], &[(23.44, 282.9), (1.54, 282.9)]);
+    }
+
+    #[test]
+    fn sol_report_regression() {
+        let report = Accrete::new(1).sol_report();
+        assert_eq!(report.bodies.len(), SOLAR_SYSTEM.len());
+        let body = |id: &str| report.bodies.iter().find(|body| body.id == id).unwrap();
+        for id in ["mercury", "venus", "earth"] {
+            assert!(body(id).density.relative_error.abs() < 0.05);
+        }
+        // Moons locked to their planets keep rotation of their orbits
+        for id in [
+            "moon", "io", "europa", "ganymede", "callisto", "titan", "triton",
+        ] {
+            assert!(body(id).day_hours.relative_error.abs() < 0.01);
+        }
+        assert!(
+            body("earth")
+                .surface_pressure_bar
+                .unwrap()
+                .relative_error
+                .abs()
+                < 0.1
+        );
+        assert!(body("jupiter").surface_temp_kelvin.is_none());
+        assert!(report.density_error > 0.0 && report.density_error < 0.5);
+    }
+}
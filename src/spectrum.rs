@@ -0,0 +1,198 @@
+use crate::consts::*;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Visible wavelengths covered by colour matching functions, nm.
+pub const VISIBLE_RANGE: (f64, f64) = (360.0, 830.0);
+
+/// Integration step over visible wavelengths, nm.
+const WAVELENGTH_STEP: f64 = 1.0;
+
+/// Linear sRGB (D65 white) from CIE XYZ.
+/// [IEC 61966-2-1, sRGB](https://www.color.org/srgb.xalter)
+const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [3.2406, -1.5372, -0.4986],
+    [-0.9689, 1.8758, 0.0415],
+    [0.0557, -0.2040, 1.0570],
+];
+
+/// Colour of a blackbody as seen by human eye.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BlackbodyColor {
+    // CIE 1931 x, y
+    pub chromaticity: (f64, f64),
+    // linear sRGB scaled to brightest channel, out of gamut channels clipped to 0
+    pub linear_rgb: [f64; 3],
+    // gamma-encoded sRGB
+    pub srgb: [f64; 3],
+}
+
+impl BlackbodyColor {
+    /// Colour of Planck spectrum at temperature in Kelvin, integrated against CIE 1931 colour matching functions.
+    pub fn new(temperature: &f64) -> Self {
+        if *temperature <= 0.0 {
+            return Self::default();
+        }
+        let xyz = blackbody_xyz(temperature);
+        let total: f64 = xyz.iter().sum();
+        let rgb: Vec<f64> = XYZ_TO_SRGB
+            .iter()
+            .map(|row| row.iter().zip(xyz.iter()).map(|(m, c)| m * c).sum::<f64>())
+            .collect();
+        let brightest = rgb.iter().cloned().fold(f64::MIN, f64::max);
+        let linear_rgb = [0, 1, 2].map(|i| (rgb[i] / brightest).max(0.0));
+
+        Self {
+            chromaticity: (
+                float_to_precision(xyz[0] / total),
+                float_to_precision(xyz[1] / total),
+            ),
+            linear_rgb: linear_rgb.map(float_to_precision),
+            srgb: linear_rgb.map(|c| float_to_precision(gamma_encode(&c))),
+        }
+    }
+
+    /// Gamma-encoded colour as hex string.
+    pub fn hex(&self) -> String {
+        let [r, g, b] = self.srgb.map(|c| (c * 255.0).round() as u8);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+/// Planck spectral radiance at wavelength in nm and temperature in Kelvin, W/(m2⋅sr⋅m).
+pub fn planck(wavelength: &f64, temperature: &f64) -> f64 {
+    let wavelength_m = wavelength * 1.0e-9;
+    2.0 * PLANCK_CONSTANT * SPEED_OF_LIGHT.powf(2.0)
+        / wavelength_m.powf(5.0)
+        / ((PLANCK_CONSTANT * SPEED_OF_LIGHT / (wavelength_m * BOLTZMANN_CONSTANT * temperature))
+            .exp()
+            - 1.0)
+}
+
+/// CIE 1931 2° colour matching functions at wavelength in nm, multi-lobe Gaussian fit.
+/// [Wyman, Sloan & Shirley 2013, Simple analytic approximations to the CIE XYZ color matching functions](https://jcgt.org/published/0002/02/01/)
+pub fn color_matching(wavelength: &f64) -> [f64; 3] {
+    let lobe = |mean: f64, below: f64, above: f64| {
+        let sigma = if *wavelength < mean { below } else { above };
+        (-0.5 * ((wavelength - mean) / sigma).powf(2.0)).exp()
+    };
+    [
+        1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+            - 0.065 * lobe(501.1, 20.4, 26.2),
+        0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1),
+        1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8),
+    ]
+}
+
+/// CIE XYZ of blackbody at temperature in Kelvin, normalized to luminance Y = 1.
+pub fn blackbody_xyz(temperature: &f64) -> [f64; 3] {
+    let xyz = visible_radiance(temperature);
+    xyz.map(|c| c / xyz[1])
+}
+
+/// Share of blackbody radiance at temperature in Kelvin weighted by luminosity function of eye (CIE Y).
+pub fn visual_share(temperature: &f64) -> f64 {
+    if *temperature <= 0.0 {
+        return 0.0;
+    }
+    visible_radiance(temperature)[1] / (SIGMA * temperature.powf(4.0) / PI)
+}
+
+/// Planck radiance integrated against colour matching functions, W/(m2⋅sr).
+fn visible_radiance(temperature: &f64) -> [f64; 3] {
+    let steps = ((VISIBLE_RANGE.1 - VISIBLE_RANGE.0) / WAVELENGTH_STEP) as usize;
+    let mut xyz = [0.0; 3];
+    for step in 0..=steps {
+        let wavelength = VISIBLE_RANGE.0 + step as f64 * WAVELENGTH_STEP;
+        let radiance = planck(&wavelength, temperature) * WAVELENGTH_STEP * 1.0e-9;
+        for (total, weight) in xyz.iter_mut().zip(color_matching(&wavelength)) {
+            *total += radiance * weight;
+        }
+    }
+    xyz
+}
+
+/// sRGB transfer function of linear channel.
+pub fn gamma_encode(channel: &f64) -> f64 {
+    match *channel <= 0.0031308 {
+        true => 12.92 * channel,
+        false => 1.055 * channel.powf(1.0 / 2.4) - 0.055,
+    }
+}
+
+/// Planck spectrum sampled at evenly spaced wavelengths in nm, radiance relative to its highest sample.
+pub fn blackbody_spectrum(
+    temperature: &f64,
+    wavelength_range: &(f64, f64),
+    samples: &usize,
+) -> Vec<(f64, f64)> {
+    let (min, max) = wavelength_range;
+    let step = match *samples > 1 {
+        true => (max - min) / (*samples - 1) as f64,
+        false => 0.0,
+    };
+    let spectrum: Vec<(f64, f64)> = (0..*samples)
+        .map(|i| {
+            let wavelength = min + i as f64 * step;
+            (wavelength, planck(&wavelength, temperature))
+        })
+        .collect();
+    let peak = spectrum
+        .iter()
+        .map(|(_, radiance)| *radiance)
+        .fold(0.0, f64::max);
+    spectrum
+        .into_iter()
+        .map(|(wavelength, radiance)| match peak > 0.0 {
+            true => (
+                float_to_precision(wavelength),
+                float_to_precision(radiance / peak),
+            ),
+            false => (float_to_precision(wavelength), 0.0),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spectrum_peaks_at_wien_wavelength() {
+        let spectrum = blackbody_spectrum(&5772.0, &(200.0, 1200.0), &1001);
+        let (peak_wavelength, _) =
+            spectrum
+                .iter()
+                .cloned()
+                .fold((0.0, 0.0), |a, b| if b.1 > a.1 { b } else { a });
+        // Wien's displacement law, b = 2.898e6 nm⋅K
+        assert!((peak_wavelength - 2.898e6 / 5772.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn cold_body_has_no_colour() {
+        for temperature in [0.0, -100.0] {
+            let color = BlackbodyColor::new(&temperature);
+            assert_eq!(color, BlackbodyColor::default());
+            assert_eq!(color.hex(), "#000000");
+            assert_eq!(visual_share(&temperature), 0.0);
+        }
+    }
+
+    #[test]
+    fn hex_clamps_channels() {
+        let color = BlackbodyColor {
+            srgb: [1.5, -0.2, 0.5],
+            ..BlackbodyColor::default()
+        };
+        assert_eq!(color.hex(), "#ff0080");
+    }
+
+    #[test]
+    fn solar_visual_share() {
+        // Luminous efficacy of sunlight is about 93 lm/W out of 683 lm/W for light at the eye's peak sensitivity
+        let share = visual_share(&5772.0);
+        assert!((share - 93.0 / 683.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::composition::Composition;
+use crate::structs::disk::DiskModel;
+use crate::structs::dust::DustBand;
+use crate::structs::planetesimal::Planetesimal;
+use crate::utils::*;
+
+use serde::{Deserialize, Serialize};
+
+/// One of the largest bodies of a belt.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BeltMember {
+    // units of solar masses
+    pub mass: f64,
+    // units of km
+    pub radius: f64,
+}
+
+/// Population of small bodies that never grew into a planet, as the main belt or the Kuiper belt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AsteroidBelt {
+    // units of AU
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    // units of solar masses
+    pub mass: f64,
+    pub composition: Composition,
+    // largest bodies, most massive first
+    pub largest_members: Vec<BeltMember>,
+}
+
+/// Number of largest members listed for each belt.
+const LARGEST_MEMBERS: usize = 3;
+
+/// Sum of k^(-1.2) over all members, the share of mass held by the largest body in a collisional cascade is its inverse.
+const CASCADE_MASS_SUM: f64 = 5.59;
+
+/// Belts of dust left in the cloud after accretion is completed.
+/// Only a small part of primordial dust survives as bodies, the rest is ground down by collisions and blown away by radiation pressure, the main belt holds about 1e-3 of its primordial mass.
+pub fn dust_belts(
+    dust_bands: &[DustBand],
+    disk: &dyn DiskModel,
+    dust_density_coeff: &f64,
+    stellar_mass: &f64,
+    stellar_luminosity: &f64,
+    snow_line: &f64,
+) -> Vec<AsteroidBelt> {
+    // Rocks closer to the star than this are evaporated
+    let sublimation_radius = 0.1 * stellar_luminosity.sqrt();
+    let mut belts: Vec<AsteroidBelt> = Vec::new();
+
+    for band in dust_bands.iter().filter(|band| band.dust_present) {
+        let inner_radius = band
+            .inner_edge
+            .max(sublimation_radius)
+            .max(disk.inner_edge(stellar_mass));
+        let outer_radius = band.outer_edge.min(disk.outer_edge(stellar_mass));
+        if outer_radius - inner_radius < 0.05 * outer_radius {
+            continue;
+        }
+        let (primordial_mass, composition) = dust_mass(
+            disk,
+            dust_density_coeff,
+            stellar_mass,
+            &inner_radius,
+            &outer_radius,
+            snow_line,
+        );
+        let mass = primordial_mass * BELT_SURVIVING_FRACTION;
+        if mass <= 0.0 {
+            continue;
+        }
+        let largest_members = largest_members(&1, &(mass / CASCADE_MASS_SUM), &composition);
+        let belt = AsteroidBelt {
+            inner_radius,
+            outer_radius,
+            mass,
+            composition,
+            largest_members,
+        };
+        belts.push(belt);
+    }
+
+    merge_belts(belts)
+}
+
+/// Dwarf planet did not clear its orbit, it is the largest member of a belt spanning its zone of gravitational effect, like Ceres or Pluto.
+pub fn dwarf_planet_belt(dwarf_planet: &Planetesimal) -> AsteroidBelt {
+    let Planetesimal {
+        a,
+        e,
+        mass,
+        radius,
+        composition,
+        ..
+    } = dwarf_planet;
+    let belt_mass = mass * CASCADE_MASS_SUM;
+    let mut members = vec![BeltMember {
+        mass: *mass,
+        radius: *radius,
+    }];
+    members.append(&mut largest_members(&2, mass, composition));
+
+    AsteroidBelt {
+        inner_radius: inner_effect_limit(a, e, mass),
+        outer_radius: outer_effect_limit(a, e, mass),
+        mass: belt_mass,
+        composition: *composition,
+        largest_members: members,
+    }
+}
+
+/// Joins belts that overlap and orders them from the star outwards.
+pub fn merge_belts(mut belts: Vec<AsteroidBelt>) -> Vec<AsteroidBelt> {
+    belts.sort_by(|b1, b2| b1.inner_radius.total_cmp(&b2.inner_radius));
+    let mut merged: Vec<AsteroidBelt> = Vec::new();
+    for belt in belts.into_iter() {
+        match merged.last_mut() {
+            Some(last) if belt.inner_radius <= last.outer_radius => last.merge(&belt),
+            _ => merged.push(belt),
+        }
+    }
+    merged
+}
+
+impl AsteroidBelt {
+    pub fn merge(&mut self, other: &AsteroidBelt) {
+        self.composition = self
+            .composition
+            .mix(&self.mass, &other.composition, &other.mass);
+        self.mass += other.mass;
+        self.inner_radius = self.inner_radius.min(other.inner_radius);
+        self.outer_radius = self.outer_radius.max(other.outer_radius);
+        self.largest_members.extend(other.largest_members.iter());
+        self.largest_members
+            .sort_by(|m1, m2| m2.mass.total_cmp(&m1.mass));
+        self.largest_members.truncate(LARGEST_MEMBERS);
+    }
+}
+
+/// Primordial dust mass (solar masses) between two radii and its mean composition. Cloud is integrated in the same units as volume swept by planetesimals.
+pub fn dust_mass(
+    disk: &dyn DiskModel,
+    dust_density_coeff: &f64,
+    stellar_mass: &f64,
+    inner_radius: &f64,
+    outer_radius: &f64,
+    snow_line: &f64,
+) -> (f64, Composition) {
+    let steps = 100;
+    let dr = (outer_radius - inner_radius) / steps as f64;
+    let mut mass = 0.0;
+    let mut composition = Composition::default();
+    for i in 0..steps {
+        let r = inner_radius + (i as f64 + 0.5) * dr;
+        let shell_mass = 4.0
+            * PI
+            * r.powf(2.0)
+            * dr
+            * reduced_mass(&PROTOPLANET_MASS)
+            * disk.dust_density(dust_density_coeff, stellar_mass, &r);
+        composition = composition.mix(&mass, &Composition::condensate(&r, snow_line), &shell_mass);
+        mass += shell_mass;
+    }
+    (mass, composition)
+}
+
+/// Largest bodies of a collisional cascade starting from the first-th one, where k-th largest body has mass of the largest one times k^(-1.2).
+/// [Dohnanyi 1969, Collisional model of asteroids and their debris](https://doi.org/10.1029/JB074i010p02531)
+fn largest_members(
+    first: &usize,
+    largest_mass: &f64,
+    composition: &Composition,
+) -> Vec<BeltMember> {
+    let density = bulk_density(composition);
+    (*first..LARGEST_MEMBERS + 1)
+        .map(|k| {
+            let mass = largest_mass * (k as f64).powf(-1.2);
+            BeltMember {
+                mass,
+                radius: volume_radius(&mass, &density),
+            }
+        })
+        .collect()
+}
+
+/// Uncompressed density (g/cc) of small body from its rock, metal and ice fractions.
+pub fn bulk_density(composition: &Composition) -> f64 {
+    let volume = composition.rock / 3.0 + composition.metal / 7.8 + composition.ice / 0.93;
+    match volume > 0.0 {
+        true => composition.solids() / volume,
+        false => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::disk::DoleDisk;
+
+    #[test]
+    fn kuiper_belt_is_icy() {
+        let bands = vec![DustBand::new(200.0, 50.0, true, true)];
+        let belts = dust_belts(
+            &bands,
+            &DoleDisk::default(),
+            &DUST_DENSITY_COEFF,
+            &1.0,
+            &1.0,
+            &2.7,
+        );
+        assert_eq!(belts.len(), 1);
+        let belt = &belts[0];
+        assert!(belt.composition.ice > 0.4);
+        assert_eq!(belt.largest_members.len(), LARGEST_MEMBERS);
+        assert!(belt.largest_members[0].mass > belt.largest_members[1].mass);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::consts::*;
+use crate::enviro::*;
+#[cfg(events_log)]
+use crate::events_log::accrete_event::AccreteEvents;
+use crate::structs::disk::{DiskModel, DoleDisk};
+use crate::structs::dust::*;
+use crate::structs::planetesimal::Planetesimal;
+use crate::structs::system::coalesce_planetesimals;
+use crate::utils::*;
+
+use rand::{Rng, RngCore};
+
+/// Inner edge of the scaled-down cloud, AU around a body of one solar mass.
+const VIRTUAL_INNER_EDGE: f64 = 0.3;
+
+/// Regular satellites of a giant planet formed by Dole's accretion inside its circumplanetary disk.
+///
+/// Accretion runs in a virtual cloud around unit mass, whose width matches the ratio of the disk edges, and is scaled back to the planet afterwards, so moon spacing follows the same laws as planets around a star.
+/// Disk spans from 3 planetary radii to 5% of the Hill sphere, total moon mass is set to about 1e-4 of the planet as in the Jovian and Saturnian systems.
+/// [Canup & Ward 2006, A common mass scaling for satellite systems of gaseous planets](https://doi.org/10.1038/nature04860)
+pub fn regular_moons(
+    planet: &Planetesimal,
+    stellar_luminosity: &f64,
+    stellar_mass: &f64,
+    snow_line: &f64,
+    cloud_eccentricity: &f64,
+    rng: &mut dyn RngCore,
+) -> Vec<Planetesimal> {
+    let hill_sphere = hill_sphere_au(&planet.a, &planet.e, &planet.mass, stellar_mass);
+    let disk_inner_edge = 3.0 * planet.radius / KM_PER_AU;
+    let disk_outer_edge = 0.05 * hill_sphere;
+    if disk_outer_edge <= disk_inner_edge {
+        return Vec::new();
+    }
+
+    let scale = disk_inner_edge / VIRTUAL_INNER_EDGE;
+    let virtual_outer_edge = disk_outer_edge / scale;
+    // Circumplanetary disk beyond snow line is heated by young planet, only outer moons keep ice as Ganymede and Callisto
+    let virtual_snow_line = match planet.a >= *snow_line {
+        true => 4.0 * VIRTUAL_INNER_EDGE,
+        false => INCREDIBLY_LARGE_NUMBER,
+    };
+    let disk = DoleDisk::default();
+    let mut dust_bands = vec![DustBand::new(virtual_outer_edge, 0.0, true, false)];
+    let mut moons: Vec<Planetesimal> = Vec::new();
+    let mut dust_left = true;
+    // Events of virtual accretion do not belong to the planetary system
+    #[cfg(events_log)]
+    let events_log = &mut AccreteEvents::new();
+
+    while dust_left {
+        let mut p = Planetesimal::new(&VIRTUAL_INNER_EDGE, &virtual_outer_edge, rng);
+        let inside_range = inner_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+        let outside_range = outer_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+        let dust_density = disk.dust_density(&DUST_DENSITY_COEFF, &1.0, &p.a);
+
+        if dust_availible(&dust_bands, &inside_range, &outside_range) {
+            accrete_dust(
+                &mut p.mass,
+                &mut p.composition,
+                &p.a,
+                &p.e,
+                &INCREDIBLY_LARGE_NUMBER,
+                &mut dust_bands,
+                cloud_eccentricity,
+                &dust_density,
+                &K,
+                &virtual_snow_line,
+                &disk,
+            );
+
+            let min = inner_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+            let max = outer_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+            update_dust_lanes(&mut dust_bands, min, max, &p.mass, &INCREDIBLY_LARGE_NUMBER);
+            compress_dust_lanes(&mut dust_bands);
+
+            moons.push(p);
+            moons.sort_by(|p1, p2| p1.a.total_cmp(&p2.a));
+            coalesce_planetesimals(
+                &1.0,
+                &1.0,
+                &mut moons,
+                rng,
+                #[cfg(events_log)]
+                events_log,
+            );
+        }
+
+        dust_left = dust_availible(&dust_bands, &VIRTUAL_INNER_EDGE, &virtual_outer_edge);
+    }
+
+    let virtual_mass: f64 = moons
+        .iter()
+        .map(|m| m.mass + m.moons.iter().map(|c| c.mass).sum::<f64>())
+        .sum();
+    let mass_scale = REGULAR_MOONS_MASS_RATIO * planet.mass / virtual_mass;
+    let orbit_zone = orbital_zone(stellar_luminosity, planet.a);
+
+    for m in moons.iter_mut() {
+        // Bodies captured during virtual accretion merge with their hosts
+        let captured_mass: f64 = m.moons.drain(..).map(|c| c.mass).sum();
+        m.rings.clear();
+        m.mass = (m.mass + captured_mass) * mass_scale;
+        m.a = float_to_precision(m.a * scale);
+        // Gas drag and tides in the disk damp eccentricities of regular moons
+        m.e = float_to_precision(m.e * 0.1);
+        m.b = semi_minor_axis(m.a, m.e);
+        m.distance_to_primary_star = planet.a;
+        m.orbit_zone = orbit_zone;
+        m.radius = kothari_radius(&m.mass, &false, &m.orbit_zone);
+        m.is_gas_giant = false;
+        m.is_dwarf_planet = false;
+        m.has_collision = false;
+        m.is_moon = true;
+        m.is_regular_moon = true;
+    }
+
+    moons
+}
+
+/// Orbit of captured irregular moon, semi-major axis in AU and eccentricity. Irregular satellites of giant planets keep wide eccentric orbits between 0.1 and 0.5 of the Hill sphere, beyond the regular ones.
+/// [Jewitt & Haghighipour 2007, Irregular Satellites of the Planets](https://doi.org/10.1146/annurev.astro.44.051905.092459)
+pub fn irregular_orbit(hill_sphere: &f64, rng: &mut dyn RngCore) -> (f64, f64) {
+    let a = rng.gen_range(0.1..0.5) * hill_sphere;
+    let e = rng.gen_range(0.1..0.6);
+    (float_to_precision(a), float_to_precision(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn jupiter_regular_moons() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut jupiter = Planetesimal::on_orbit(5.2, 0.05, "jupiter".to_string());
+        jupiter.mass = JUPITER_MASS_IN_EARTH_MASSES / EARTH_MASSES_PER_SOLAR_MASS;
+        jupiter.radius = 71492.0;
+        jupiter.is_gas_giant = true;
+
+        let moons = regular_moons(&jupiter, &1.0, &1.0, &2.7, &0.2, &mut rng);
+        assert!(!moons.is_empty());
+        let total: f64 = moons.iter().map(|m| m.mass).sum();
+        assert!((total / jupiter.mass - REGULAR_MOONS_MASS_RATIO).abs() < 1.0e-9);
+        assert!(moons.iter().all(|m| m.is_regular_moon && m.e < 0.03));
+
+        let hill_sphere = hill_sphere_au(&jupiter.a, &jupiter.e, &jupiter.mass, &1.0);
+        let (a, e) = irregular_orbit(&hill_sphere, &mut rng);
+        assert!(moons.iter().all(|m| m.a < a) && a * (1.0 + e) < hill_sphere);
+    }
+}
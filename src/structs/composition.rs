@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+/// Bulk composition of a body as mass fractions. Rock, metal and ice come from swept dust, gas is hydrogen and helium captured above critical mass.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Composition {
+    pub rock: f64,
+    pub metal: f64,
+    pub ice: f64,
+    pub gas: f64,
+}
+
+impl Default for Composition {
+    /// Chondritic rock with Earth-like iron content.
+    fn default() -> Self {
+        Self {
+            rock: 0.68,
+            metal: 0.32,
+            ice: 0.0,
+            gas: 0.0,
+        }
+    }
+}
+
+impl Composition {
+    /// Solids condensing at given orbital radius (AU). Beyond the snow line water ice makes about half of the solids (cometary ratio), inside it metal content rises towards the star as in Mercury.
+    pub fn condensate(orbital_radius: &f64, snow_line: &f64) -> Self {
+        if orbital_radius >= snow_line {
+            return Self::icy();
+        }
+        let metal = 0.3 + 0.4 * (-orbital_radius / (0.15 * snow_line)).exp();
+        Self {
+            rock: 1.0 - metal,
+            metal,
+            ice: 0.0,
+            gas: 0.0,
+        }
+    }
+
+    /// Solids condensed beyond the snow line.
+    pub fn icy() -> Self {
+        Self {
+            rock: 0.35,
+            metal: 0.15,
+            ice: 0.5,
+            gas: 0.0,
+        }
+    }
+
+    /// Composition of two masses combined.
+    pub fn mix(&self, mass: &f64, other: &Composition, other_mass: &f64) -> Self {
+        let total = mass + other_mass;
+        if total <= 0.0 {
+            return *self;
+        }
+        let w1 = mass / total;
+        let w2 = other_mass / total;
+        Self {
+            rock: self.rock * w1 + other.rock * w2,
+            metal: self.metal * w1 + other.metal * w2,
+            ice: self.ice * w1 + other.ice * w2,
+            gas: self.gas * w1 + other.gas * w2,
+        }
+    }
+
+    /// Same composition with given gas fraction, solids scaled down to fill the rest.
+    pub fn with_gas(&self, gas: &f64) -> Self {
+        let gas = gas.clamp(0.0, 1.0);
+        let solids = self.rock + self.metal + self.ice;
+        if solids <= 0.0 {
+            return Self {
+                gas,
+                ..Default::default()
+            };
+        }
+        let scale = (1.0 - gas) / solids;
+        Self {
+            rock: self.rock * scale,
+            metal: self.metal * scale,
+            ice: self.ice * scale,
+            gas,
+        }
+    }
+
+    /// Fraction of rock, metal and ice.
+    pub fn solids(&self) -> f64 {
+        self.rock + self.metal + self.ice
+    }
+
+    /// Iron core share of the refractory (rock + metal) part.
+    pub fn core_mass_fraction(&self) -> f64 {
+        let refractory = self.rock + self.metal;
+        match refractory > 0.0 {
+            true => self.metal / refractory,
+            false => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mix_conserves_fractions() {
+        let rocky = Composition::condensate(&1.0, &2.7);
+        let icy = Composition::condensate(&5.0, &2.7);
+        let mixed = rocky.mix(&1.0, &icy, &3.0);
+        assert!((mixed.solids() + mixed.gas - 1.0).abs() < 1.0e-12);
+        assert!((mixed.ice - 0.375).abs() < 1.0e-12);
+    }
+
+    #[test]
+    fn metal_rich_near_star() {
+        let mercury = Composition::condensate(&0.39, &2.7);
+        let earth = Composition::condensate(&1.0, &2.7);
+        assert!(mercury.metal > earth.metal);
+        assert_eq!(earth.ice, 0.0);
+    }
+}
@@ -0,0 +1,339 @@
+use crate::consts::*;
+use crate::structs::dust::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Protoplanetary disk profile used during accretion.
+/// Densities are volume densities in the units of Dole's paper and are normalized by "A" (dust_density_coeff), so every model stays comparable with the default cloud.
+pub trait DiskModel {
+    /// Density of dust at orbital radius (AU).
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64;
+
+    /// Density of gas and dust at orbital radius (AU) swept by a body heavier than critical mass.
+    fn gas_density(
+        &self,
+        k: &f64,
+        dust_density: &f64,
+        crit_mass: &f64,
+        mass: &f64,
+        _orbital_radius: &f64,
+    ) -> f64 {
+        get_mass_density(k, dust_density, crit_mass, mass)
+    }
+
+    /// Inner edge of the disk in AU.
+    fn inner_edge(&self, _stellar_mass: &f64) -> f64 {
+        0.0
+    }
+
+    /// Outer edge of the disk in AU.
+    fn outer_edge(&self, stellar_mass: &f64) -> f64 {
+        stellar_dust_limit(stellar_mass)
+    }
+
+    /// Distance (AU) beyond which water ice condenses.
+    fn snow_line(&self, stellar_luminosity: &f64) -> f64 {
+        SNOW_LINE_AU * stellar_luminosity.sqrt()
+    }
+
+    /// Dust bands the accretion starts with.
+    fn dust_bands(&self, stellar_mass: &f64) -> Vec<DustBand> {
+        vec![DustBand::new(
+            self.outer_edge(stellar_mass),
+            self.inner_edge(stellar_mass),
+            true,
+            true,
+        )]
+    }
+}
+
+/// "The density of dust (p1) within the cloud depends on a function of the form p1 = A exp (-arl/n)"
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DoleDisk {
+    pub alpha: f64,
+    pub n: f64,
+}
+
+impl Default for DoleDisk {
+    fn default() -> Self {
+        Self { alpha: ALPHA, n: N }
+    }
+}
+
+impl DiskModel for DoleDisk {
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        dust_density_coeff
+            * stellar_mass.sqrt()
+            * (-self.alpha * orbital_radius.powf(1.0 / self.n)).exp()
+    }
+}
+
+/// Minimum-mass solar nebula (Weidenschilling 1977, Hayashi 1981), density falls as a power law of radius.
+/// Normalized to match Dole's cloud at 1 AU. Gas follows its own power law, so gas to dust ratio equals K only at 1 AU when exponents differ (viscous disks have gas exponent close to 1.0).
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PowerLawDisk {
+    pub exponent: f64,
+    pub gas_exponent: f64,
+}
+
+impl Default for PowerLawDisk {
+    fn default() -> Self {
+        Self {
+            exponent: 1.5,
+            gas_exponent: 1.5,
+        }
+    }
+}
+
+impl DiskModel for PowerLawDisk {
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        dust_density_coeff
+            * stellar_mass.sqrt()
+            * (-ALPHA).exp()
+            * orbital_radius.powf(-self.exponent)
+    }
+
+    fn gas_density(
+        &self,
+        k: &f64,
+        dust_density: &f64,
+        crit_mass: &f64,
+        mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        let gas_to_dust = k * orbital_radius.powf(self.exponent - self.gas_exponent);
+        get_mass_density(&gas_to_dust, dust_density, crit_mass, mass)
+    }
+}
+
+/// Dole's cloud cut by photoevaporation or a stellar flyby. Edges are in AU for a solar mass star and scale with cube root of stellar mass.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TruncatedDisk {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+}
+
+impl Default for TruncatedDisk {
+    fn default() -> Self {
+        Self {
+            inner_radius: 0.0,
+            outer_radius: 30.0,
+        }
+    }
+}
+
+impl DiskModel for TruncatedDisk {
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        if *orbital_radius < self.inner_edge(stellar_mass)
+            || *orbital_radius > self.outer_edge(stellar_mass)
+        {
+            return 0.0;
+        }
+        DoleDisk::default().dust_density(dust_density_coeff, stellar_mass, orbital_radius)
+    }
+
+    fn inner_edge(&self, stellar_mass: &f64) -> f64 {
+        self.inner_radius * stellar_mass.powf(1.0 / 3.0)
+    }
+
+    fn outer_edge(&self, stellar_mass: &f64) -> f64 {
+        (self.outer_radius * stellar_mass.powf(1.0 / 3.0)).min(stellar_dust_limit(stellar_mass))
+    }
+}
+
+/// Empty lane in a disk, AU for a solar mass star.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiskGap {
+    pub inner_edge: f64,
+    pub outer_edge: f64,
+}
+
+/// Dole's cloud with lanes already cleared, as seen in ALMA images of young disks (HL Tau, TW Hya). Gaps scale with cube root of stellar mass, as disk edges do.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GappedDisk {
+    pub gaps: Vec<DiskGap>,
+}
+
+impl Default for GappedDisk {
+    fn default() -> Self {
+        Self {
+            gaps: vec![
+                DiskGap {
+                    inner_edge: 2.5,
+                    outer_edge: 4.0,
+                },
+                DiskGap {
+                    inner_edge: 12.0,
+                    outer_edge: 16.0,
+                },
+            ],
+        }
+    }
+}
+
+impl GappedDisk {
+    fn scaled_gaps(&self, stellar_mass: &f64) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let scale = stellar_mass.powf(1.0 / 3.0);
+        self.gaps
+            .iter()
+            .map(move |gap| (gap.inner_edge * scale, gap.outer_edge * scale))
+    }
+
+    fn in_gap(&self, stellar_mass: &f64, orbital_radius: &f64) -> bool {
+        self.scaled_gaps(stellar_mass)
+            .any(|(inner_edge, outer_edge)| {
+                *orbital_radius > inner_edge && *orbital_radius < outer_edge
+            })
+    }
+}
+
+impl DiskModel for GappedDisk {
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        if self.in_gap(stellar_mass, orbital_radius) {
+            return 0.0;
+        }
+        DoleDisk::default().dust_density(dust_density_coeff, stellar_mass, orbital_radius)
+    }
+
+    fn dust_bands(&self, stellar_mass: &f64) -> Vec<DustBand> {
+        let mut dust_bands = vec![DustBand::new(
+            self.outer_edge(stellar_mass),
+            self.inner_edge(stellar_mass),
+            true,
+            true,
+        )];
+        for (inner_edge, outer_edge) in self.scaled_gaps(stellar_mass) {
+            update_dust_lanes(&mut dust_bands, inner_edge, outer_edge, &0.0, &1.0);
+        }
+        compress_dust_lanes(&mut dust_bands);
+        dust_bands
+    }
+}
+
+/// Disk models available from configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Disk {
+    Dole(DoleDisk),
+    MinimumMassSolarNebula(PowerLawDisk),
+    Truncated(TruncatedDisk),
+    Gapped(GappedDisk),
+}
+
+impl Default for Disk {
+    fn default() -> Self {
+        Disk::Dole(DoleDisk::default())
+    }
+}
+
+impl Disk {
+    fn model(&self) -> &dyn DiskModel {
+        match self {
+            Disk::Dole(disk) => disk,
+            Disk::MinimumMassSolarNebula(disk) => disk,
+            Disk::Truncated(disk) => disk,
+            Disk::Gapped(disk) => disk,
+        }
+    }
+}
+
+impl DiskModel for Disk {
+    fn dust_density(
+        &self,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        self.model()
+            .dust_density(dust_density_coeff, stellar_mass, orbital_radius)
+    }
+
+    fn gas_density(
+        &self,
+        k: &f64,
+        dust_density: &f64,
+        crit_mass: &f64,
+        mass: &f64,
+        orbital_radius: &f64,
+    ) -> f64 {
+        self.model()
+            .gas_density(k, dust_density, crit_mass, mass, orbital_radius)
+    }
+
+    fn inner_edge(&self, stellar_mass: &f64) -> f64 {
+        self.model().inner_edge(stellar_mass)
+    }
+
+    fn outer_edge(&self, stellar_mass: &f64) -> f64 {
+        self.model().outer_edge(stellar_mass)
+    }
+
+    fn snow_line(&self, stellar_luminosity: &f64) -> f64 {
+        self.model().snow_line(stellar_luminosity)
+    }
+
+    fn dust_bands(&self, stellar_mass: &f64) -> Vec<DustBand> {
+        self.model().dust_bands(stellar_mass)
+    }
+}
+
+pub fn stellar_dust_limit(stellar_mass_ratio: &f64) -> f64 {
+    200.0 * stellar_mass_ratio.powf(1.0 / 3.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gapped_disk_bands() {
+        let disk = GappedDisk::default();
+        let bands = disk.dust_bands(&1.0);
+        assert_eq!(bands.len(), 5);
+        assert!(!dust_availible(&bands, &2.6, &3.9));
+        assert!(dust_availible(&bands, &4.1, &11.0));
+
+        let bands = disk.dust_bands(&0.125);
+        assert!(!dust_availible(&bands, &1.3, &1.9));
+        assert!(dust_availible(&bands, &2.1, &5.9));
+    }
+
+    #[test]
+    fn mmsn_matches_dole_at_one_au() {
+        let dole = DoleDisk::default().dust_density(&DUST_DENSITY_COEFF, &1.0, &1.0);
+        let mmsn = PowerLawDisk::default().dust_density(&DUST_DENSITY_COEFF, &1.0, &1.0);
+        assert!((dole - mmsn).abs() < 1.0e-12);
+
+        let viscous = PowerLawDisk {
+            gas_exponent: 1.0,
+            ..PowerLawDisk::default()
+        };
+        let gas = |disk: &PowerLawDisk, r: f64| disk.gas_density(&K, &1.0, &0.01, &1.0, &r);
+        assert!((gas(&viscous, 1.0) - gas(&PowerLawDisk::default(), 1.0)).abs() < 1.0e-12);
+        assert!(gas(&viscous, 10.0) > gas(&PowerLawDisk::default(), 10.0));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::consts::*;
+use crate::structs::composition::Composition;
+use crate::structs::disk::DiskModel;
 use crate::utils::*;
 
 use serde::{Deserialize, Serialize};
@@ -35,8 +37,10 @@ pub fn dust_availible(dust_bands: &[DustBand], inside_range: &f64, outside_range
 
 /// "The center of mass is occupied by a star with a mass of one unit (one solar mass). All particles in the cloud are moving on elliptical orbits, with the center of mass at one focus. The density of dust (p1) within the cloud depends on a function of the form p1 = A exp (-arl/n). The overall density of gas and dust (p2) within the cloud equals Kpl, where r is distance from the center of mass (in astronomical units, a.u.) and A. a. n. and K (the vas/dust ratio) are constants."
 /// "There is a spherically symmetrical cloud of dust and gas with a constant ratio of gas to dust, the density decreasing with distance from the center."
+/// Composition is accumulated from every band in proportion to the mass swept from it.
 pub fn accrete_dust(
     mass: &mut f64,
+    composition: &mut Composition,
     a: &f64,
     e: &f64,
     crit_mass: &f64,
@@ -44,14 +48,17 @@ pub fn accrete_dust(
     cloud_eccentricity: &f64,
     dust_density: &f64,
     k: &f64,
+    snow_line: &f64,
+    disk: &dyn DiskModel,
 ) {
     let mut new_mass = *mass;
+    let mut swept = *composition;
     loop {
         *mass = new_mass;
         new_mass = 0.0;
 
         for d in dust_bands.iter_mut() {
-            new_mass += collect_dust(
+            let band_mass = collect_dust(
                 mass,
                 a,
                 e,
@@ -59,15 +66,27 @@ pub fn accrete_dust(
                 cloud_eccentricity,
                 dust_density,
                 k,
+                disk,
                 d,
             );
+            if band_mass > 0.0 {
+                let dust_mass = swept_volume(mass, a, e, cloud_eccentricity, d) * dust_density;
+                let band_composition =
+                    band_condensate(mass, a, e, cloud_eccentricity, snow_line, d)
+                        .with_gas(&(1.0 - dust_mass / band_mass));
+                swept = swept.mix(&new_mass, &band_composition, &band_mass);
+                new_mass += band_mass;
+            }
         }
 
-        if !((new_mass - *mass) >= (0.0001 * *mass)) {
+        if new_mass - *mass < 0.0001 * *mass || (new_mass - *mass).is_nan() {
             break;
         }
     }
     *mass = new_mass;
+    if new_mass > 0.0 {
+        *composition = swept;
+    }
 }
 
 pub fn collect_dust(
@@ -78,7 +97,28 @@ pub fn collect_dust(
     cloud_eccentricity: &f64,
     dust_density: &f64,
     k: &f64,
+    disk: &dyn DiskModel,
     band: &mut DustBand,
+) -> f64 {
+    if !band.dust_present {
+        return 0.0;
+    }
+
+    let density = match !band.gas_present || mass < crit_mass {
+        true => *dust_density,
+        false => disk.gas_density(k, dust_density, crit_mass, mass, a),
+    };
+
+    swept_volume(mass, a, e, cloud_eccentricity, band) * density
+}
+
+/// Volume of the band swept by planetesimal, zero if band is out of reach.
+pub fn swept_volume(
+    mass: &f64,
+    a: &f64,
+    e: &f64,
+    cloud_eccentricity: &f64,
+    band: &DustBand,
 ) -> f64 {
     let mut r_inner = inner_swept_limit(a, e, mass, cloud_eccentricity);
     let r_outer = outer_swept_limit(a, e, mass, cloud_eccentricity);
@@ -87,14 +127,10 @@ pub fn collect_dust(
         r_inner = 0.0;
     }
 
-    if band.outer_edge <= r_inner || band.inner_edge >= r_outer || !band.dust_present {
+    if band.outer_edge <= r_inner || band.inner_edge >= r_outer {
         return 0.0;
     };
 
-    let density = match !band.gas_present || mass < crit_mass {
-        true => *dust_density,
-        false => get_mass_density(k, dust_density, crit_mass, mass),
-    };
     let bandwidth = r_outer - r_inner;
     let temp1 = match r_outer - band.outer_edge > 0.0 {
         true => r_outer - band.outer_edge,
@@ -107,9 +143,30 @@ pub fn collect_dust(
     let width = bandwidth - temp1 - temp2;
     let term1 = 4.0 * PI * a.powf(2.0);
     let term2 = 1.0 - e * (temp1 - temp2) / bandwidth;
-    let volume = term1 * reduced_mass(mass) * width * term2;
+    term1 * reduced_mass(mass) * width * term2
+}
 
-    volume * density
+/// Solids swept from the band by planetesimal of given mass, condensed inside and outside of the snow line in proportion to swept width.
+pub fn band_condensate(
+    mass: &f64,
+    a: &f64,
+    e: &f64,
+    cloud_eccentricity: &f64,
+    snow_line: &f64,
+    band: &DustBand,
+) -> Composition {
+    let r_inner = inner_swept_limit(a, e, mass, cloud_eccentricity).max(0.0);
+    let r_outer = outer_swept_limit(a, e, mass, cloud_eccentricity);
+    let low = band.inner_edge.max(r_inner);
+    let high = band.outer_edge.min(r_outer);
+    if high <= low {
+        return Composition::condensate(a, snow_line);
+    }
+    let inside = ((snow_line - low) / (high - low)).clamp(0.0, 1.0);
+    let inner_part =
+        Composition::condensate(&((low + low.max(high.min(*snow_line))) / 2.0), snow_line);
+    let outer_part = Composition::condensate(snow_line, snow_line);
+    inner_part.mix(&inside, &outer_part, &(1.0 - inside))
 }
 
 pub fn update_dust_lanes(
@@ -180,6 +237,34 @@ pub fn get_mass_density(k: &f64, dust_density: &f64, critical_mass: &f64, mass:
     k * dust_density / (1.0 + (critical_mass / mass).sqrt() * (k - 1.0))
 }
 
-pub fn dust_density(dust_density_coeff: &f64, stellar_mass: &f64, oribital_radius: &f64) -> f64 {
-    dust_density_coeff * stellar_mass.sqrt() * (-ALPHA * oribital_radius.powf(1.0 / N)).exp()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::disk::DoleDisk;
+
+    #[test]
+    fn composition_accumulates_across_snow_line() {
+        let disk = DoleDisk::default();
+        let mut bands = vec![DustBand::new(200.0, 0.0, true, true)];
+        let mut mass = PROTOPLANET_MASS;
+        let mut composition = Composition::default();
+        let dust_density = disk.dust_density(&DUST_DENSITY_COEFF, &1.0, &2.7);
+        accrete_dust(
+            &mut mass,
+            &mut composition,
+            &2.7,
+            &0.2,
+            &INCREDIBLY_LARGE_NUMBER,
+            &mut bands,
+            &0.2,
+            &dust_density,
+            &K,
+            &2.7,
+            &disk,
+        );
+        assert!(mass > PROTOPLANET_MASS);
+        assert!(composition.ice > 0.0 && composition.ice < Composition::icy().ice);
+        assert_eq!(composition.gas, 0.0);
+        assert!((composition.solids() - 1.0).abs() < 1.0e-12);
+    }
 }
@@ -1,18 +1,34 @@
 #![allow(unused_imports)]
+pub mod asteroid_belt;
+pub mod circumplanetary;
+pub mod composition;
+pub mod disk;
 pub mod dust;
+pub mod pinned;
 pub mod planetesimal;
 pub mod primary_star;
+pub mod reservoir;
 pub mod ring;
 pub mod system;
 
+pub use asteroid_belt::AsteroidBelt;
+pub use composition::Composition;
+pub use disk::{Disk, DiskModel};
 pub use dust::DustBand;
+pub use pinned::PinnedPlanet;
 pub use planetesimal::Planetesimal;
 pub use primary_star::PrimaryStar;
+pub use reservoir::CometaryReservoir;
 pub use ring::Ring;
 pub use system::System;
 
+use asteroid_belt::*;
+use composition::*;
+use disk::*;
 use dust::*;
+use pinned::*;
 use planetesimal::*;
 use primary_star::*;
+use reservoir::*;
 use ring::*;
 use system::*;
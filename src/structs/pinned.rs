@@ -0,0 +1,174 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::*;
+use crate::utils::*;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Planet placed before accretion, the rest of the system forms around it.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PinnedPlanet {
+    // semi-major axis, AU
+    pub a: f64,
+    pub e: f64,
+    pub earth_masses: f64,
+    // merge with crossing bodies like any other planet, otherwise kept as given
+    pub can_grow: bool,
+}
+
+impl PinnedPlanet {
+    /// Protected planet kept as given.
+    pub fn new(a: f64, e: f64, earth_masses: f64) -> Self {
+        Self {
+            a,
+            e,
+            earth_masses,
+            can_grow: false,
+        }
+    }
+
+    /// Orbit must be bound and elliptic, mass positive.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.a.is_finite() && self.a > 0.0) {
+            return Err(format!(
+                "Pinned planet semi-major axis must be positive, got {}",
+                self.a
+            ));
+        }
+        if !(0.0..1.0).contains(&self.e) {
+            return Err(format!(
+                "Pinned planet eccentricity must be in 0.0-1.0, got {}",
+                self.e
+            ));
+        }
+        if !(self.earth_masses.is_finite() && self.earth_masses > 0.0) {
+            return Err(format!(
+                "Pinned planet mass must be positive, got {}",
+                self.earth_masses
+            ));
+        }
+        Ok(())
+    }
+
+    /// Planet that has already swept its dust, gas giant above critical mass with envelope of the mass beyond it.
+    pub fn planetesimal(
+        &self,
+        primary_star: &PrimaryStar,
+        b: &f64,
+        snow_line: &f64,
+        rng: &mut dyn RngCore,
+    ) -> Planetesimal {
+        let PrimaryStar {
+            stellar_luminosity,
+            stellar_mass,
+            ..
+        } = primary_star;
+        let mass = self.earth_masses / EARTH_MASSES_PER_SOLAR_MASS;
+        let crit_mass = critical_limit(b, &self.a, &self.e, stellar_luminosity);
+        let mut planet = Planetesimal::on_orbit(self.a, self.e, random_id(rng));
+        planet.mass = mass;
+        planet.is_gas_giant = mass > crit_mass;
+        let gas = match planet.is_gas_giant {
+            true => 1.0 - crit_mass / mass,
+            false => 0.0,
+        };
+        planet.composition = Composition::condensate(&self.a, snow_line).with_gas(&gas);
+        planet.orbit_clearing = clearing_neightbourhood(&mass, &self.a, stellar_mass);
+        planet.is_dwarf_planet = planet.orbit_clearing < 1.0;
+        planet.orbit_zone = orbital_zone(stellar_luminosity, self.a);
+        planet.radius = kothari_radius(&mass, &planet.is_gas_giant, &planet.orbit_zone);
+        planet.is_pinned = true;
+        planet.is_protected = !self.can_grow;
+        planet
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::system::planetesimals_intersect;
+    use crate::Accrete;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn system_forms_around_pinned_planets() {
+        let mut accrete = Accrete::new(4);
+        accrete.stellar_mass = 1.0;
+        accrete.pinned_planets = vec![
+            PinnedPlanet::new(1.0, 0.0167, 1.0),
+            PinnedPlanet::new(5.2, 0.048, 317.8),
+        ];
+        let system = accrete.planetary_system();
+        let pinned: Vec<&Planetesimal> = system.planets.iter().filter(|p| p.is_pinned).collect();
+        assert_eq!(pinned.len(), 2);
+        assert!(
+            pinned[0].a == 1.0
+                && (pinned[0].earth_masses - 1.0).abs() < 1.0e-6
+                && pinned[0].moons.is_empty()
+        );
+        // XUV evaporates a sliver of the envelope, reported without changing the given mass
+        assert!(pinned[1].is_gas_giant && (pinned[1].earth_masses - 317.8).abs() < 1.0e-6);
+        assert!(pinned[1].escape.envelope_mass_lost > 0.0);
+        assert!(system.planets.len() > 2);
+        // Bodies scattered away by protected pins feed the cometary reservoir
+        assert!(system.scattered_mass > 0.0);
+
+        // Growing Earth captures crossing neighbours as moons and shifts its orbit
+        let mut accrete = Accrete::new(9);
+        accrete.stellar_mass = 1.0;
+        accrete.pinned_planets = vec![PinnedPlanet {
+            can_grow: true,
+            ..PinnedPlanet::new(1.0, 0.0167, 1.0)
+        }];
+        let system = accrete.planetary_system();
+        let earth = system.planets.iter().find(|p| p.is_pinned).unwrap();
+        assert!(!earth.is_protected && earth.a != 1.0 && !earth.moons.is_empty());
+
+        // Invalid pins are skipped
+        let mut accrete = Accrete::new(4);
+        accrete.pinned_planets = vec![
+            PinnedPlanet::new(0.0, 0.0, 1.0),
+            PinnedPlanet::new(1.0, 1.0, 1.0),
+            PinnedPlanet::new(1.0, 0.0, -1.0),
+        ];
+        assert!(accrete
+            .pinned_planets
+            .iter()
+            .all(|pinned| pinned.validate().is_err()));
+        let system = accrete.planetary_system();
+        assert!(system
+            .planets
+            .iter()
+            .all(|p| !p.is_pinned && p.a.is_finite()));
+        let err = accrete.try_planetary_system().unwrap_err();
+        assert!(err.contains("semi-major axis") && err.contains("pinned planet 0"));
+    }
+
+    #[test]
+    fn growing_pin_is_captured_by_larger_body() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut earth = Planetesimal::on_orbit(1.0, 0.0167, "earth".to_string());
+        earth.mass = 1.0 / EARTH_MASSES_PER_SOLAR_MASS;
+        earth.radius = EARTH_RADIUS_IN_KM;
+        earth.is_pinned = true;
+        let mut giant = Planetesimal::on_orbit(1.3, 0.05, "giant".to_string());
+        giant.mass = 300.0 / EARTH_MASSES_PER_SOLAR_MASS;
+        giant.is_gas_giant = true;
+        planetesimals_intersect(
+            &mut earth,
+            &mut giant,
+            &1.0,
+            &1.0,
+            &mut rng,
+            #[cfg(events_log)]
+            &mut Vec::new(),
+        );
+        assert!(giant.id == "giant" && !giant.is_pinned);
+        assert!(giant
+            .moons
+            .iter()
+            .any(|moon| moon.id == "earth" && moon.is_pinned));
+    }
+}
@@ -1,8 +1,20 @@
+use crate::activity::{StellarActivity, XuvExposure};
+use crate::climate::*;
 use crate::consts::*;
 use crate::enviro::*;
+use crate::escape::AtmosphericEscape;
 #[cfg(events_log)]
 use crate::events_log::accrete_event::AccreteEvents;
+use crate::habitability::Habitability;
+use crate::habitable_zone::*;
+use crate::interior::*;
+use crate::lagrange::*;
+use crate::magnetosphere::Magnetosphere;
+use crate::obliquity::Obliquity;
+use crate::seasons::Seasons;
 use crate::structs::*;
+use crate::structure::StructureModel;
+use crate::tides::TidalEvolution;
 use crate::utils::*;
 
 use rand::distributions::Alphanumeric;
@@ -19,6 +31,10 @@ pub struct Planetesimal {
     pub b: f64,
     // eccentricity of the orbit, unitless
     pub e: f64,
+    // direction of periapsis in the common plane of orbits, degrees
+    pub longitude_of_periapsis: f64,
+    // orbital phase at epoch of sky positions, degrees
+    pub mean_anomaly: f64,
     pub distance_to_primary_star: f64,
     pub mass: f64,
     pub earth_masses: f64,
@@ -30,8 +46,10 @@ pub struct Planetesimal {
     // density, g/cc
     pub density: f64,
     pub resonant_period: bool,
-    // units of degrees
+    // units of degrees, above 90 for retrograde rotation
     pub axial_tilt: f64,
+    // rotation direction and long-term variation of axial tilt
+    pub obliquity: Obliquity,
     // units of cm/sec
     pub escape_velocity: f64,
     // units of cm/sec2
@@ -46,7 +64,10 @@ pub struct Planetesimal {
     pub length_of_year: f64,
     pub molecule_weight: f64,
     pub volatile_gas_inventory: f64,
-    pub greenhouse_effect: bool,
+    // greenhouse state by received stellar flux and surface temperature
+    pub climate_state: ClimateState,
+    // surface temperature iteration settled before iteration limit
+    pub surface_temp_converged: bool,
     pub albedo: f64,
     pub is_tidally_locked: bool,
     pub surface_pressure_bar: f64,
@@ -62,15 +83,46 @@ pub struct Planetesimal {
     pub moons: Vec<Planetesimal>,
     pub rings: Vec<Ring>,
     pub is_moon: bool,
+    // moon formed in circumplanetary disk, captured (irregular) moons have it false
+    pub is_regular_moon: bool,
+    // moon confining edge of a ring or orbiting inside its gap
+    pub is_shepherd_moon: bool,
+    // placed by configuration before accretion
+    pub is_pinned: bool,
+    // mass and composition kept as given (protected pinned planets and presets), bodies crossing its orbit are scattered away
+    pub is_protected: bool,
+    // measured body of a preset, keeps given tilt and calendar
+    pub is_known: bool,
     // orbit clearing less than 1.0 is threshold between planet and dearf planet
     pub orbit_clearing: f64,
     // dwarf planet may have an astroid field or other objects on its orbit
     pub is_dwarf_planet: bool,
     pub hill_sphere: f64,
+    // equilibrium points with host body
+    pub lagrange_points: LagrangePoints,
+    // asteroids at L4 and L5, generated for massive planets only
+    pub trojans: Vec<TrojanSwarm>,
     pub tectonic_activity: bool,
-    pub magnetosphere: bool,
+    // intrinsic magnetic field and its shielding from stellar wind
+    pub magnetosphere: Magnetosphere,
+    // high-energy radiation and flares received from the star
+    pub xuv: XuvExposure,
+    // thermal and hydrodynamic loss of gases over system age
+    pub escape: AtmosphericEscape,
     // if planet had collision with other objects
     pub has_collision: bool,
+    // mass fractions of rock, metal, ice and gas accumulated during accretion
+    pub composition: Composition,
+    // layered structure and heat budget
+    pub interior: Interior,
+    // spin braked by tides of host body and spin-orbit resonance
+    pub tides: TidalEvolution,
+    // calendar and seasonal temperatures, moons share calendar of their planet
+    pub seasons: Seasons,
+    // latitudinal temperatures and ice caps, derived for planets only
+    pub climate: Climate,
+    // surface habitability and hazards against it
+    pub habitability: Habitability,
     pub id: String,
 }
 
@@ -82,13 +134,20 @@ impl Planetesimal {
     ) -> Self {
         let a = semi_major_axis(*planetesimal_inner_bound, *planetesimal_outer_bound, rng);
         let e = random_eccentricity(rng);
-        let b = semi_minor_axis(a, e);
         let id = random_id(rng);
+        Self::on_orbit(a, e, id)
+    }
+
+    /// Protoplanet of given id on orbit with semi-major axis in AU and eccentricity.
+    pub fn on_orbit(a: f64, e: f64, id: String) -> Self {
+        let b = semi_minor_axis(a, e);
 
         Planetesimal {
             a,
             b,
             e,
+            longitude_of_periapsis: 0.0,
+            mean_anomaly: 0.0,
             distance_to_primary_star: a,
             mass: PROTOPLANET_MASS,
             earth_masses: 0.0,
@@ -100,13 +159,15 @@ impl Planetesimal {
             day_hours: 0.0,
             resonant_period: false,
             axial_tilt: 0.0,
+            obliquity: Obliquity::default(),
             escape_velocity: 0.0,
             surface_accel: 0.0,
             surface_grav: 0.0,
             rms_velocity: 0.0,
             molecule_weight: 0.0,
             volatile_gas_inventory: 0.0,
-            greenhouse_effect: false,
+            climate_state: ClimateState::Frozen,
+            surface_temp_converged: true,
             albedo: 0.0,
             surface_temp_kelvin: 0.0,
             day_temp_kelvin: 0.0,
@@ -124,13 +185,28 @@ impl Planetesimal {
             escape_velocity_km_per_sec: 0.0,
             is_tidally_locked: false,
             is_moon: false,
+            is_regular_moon: false,
+            is_shepherd_moon: false,
+            is_pinned: false,
+            is_protected: false,
+            is_known: false,
             is_gas_giant: false,
             is_dwarf_planet: false,
             orbit_clearing: 0.0,
             hill_sphere: 0.0,
+            lagrange_points: LagrangePoints::default(),
+            trojans: Vec::new(),
             tectonic_activity: false,
-            magnetosphere: false,
+            magnetosphere: Magnetosphere::default(),
+            xuv: XuvExposure::default(),
+            escape: AtmosphericEscape::default(),
             has_collision: false,
+            composition: Composition::default(),
+            interior: Interior::default(),
+            tides: TidalEvolution::default(),
+            seasons: Seasons::default(),
+            climate: Climate::default(),
+            habitability: Habitability::default(),
             id,
         }
     }
@@ -139,59 +215,150 @@ impl Planetesimal {
         &mut self,
         stellar_luminosity: &f64,
         stellar_mass: &f64,
-        main_seq_age: &f64,
+        age: &f64,
         ecosphere: &(f64, f64),
+        stellar_wind: &f64,
+        activity: &StellarActivity,
+        hydrodynamic_escape: &bool,
+        structure: &dyn StructureModel,
         rng: &mut dyn RngCore,
     ) {
-        if !self.is_moon {
-            self.orbit_zone = orbital_zone(stellar_luminosity, self.a);
-        }
-        if self.is_gas_giant {
-            self.density = empirical_density(
-                &self.mass,
-                &self.distance_to_primary_star,
-                &ecosphere.1,
-                &self.is_gas_giant,
-            );
-            self.radius = volume_radius(&self.mass, &self.density);
-        } else {
-            self.density = volume_density(&self.mass, &self.radius);
-            self.radius = kothari_radius(&self.mass, &self.is_gas_giant, &self.orbit_zone);
+        self.distance_to_primary_star = self.a;
+        self.orbit_zone = orbital_zone(stellar_luminosity, self.distance_to_primary_star);
+        self.derive_environment(
+            stellar_mass,
+            stellar_mass,
+            stellar_luminosity,
+            age,
+            ecosphere,
+            stellar_wind,
+            activity,
+            hydrodynamic_escape,
+            structure,
+            rng,
+        );
+        self.seasons = Seasons::new(self, stellar_luminosity, rng);
+        self.climate = Climate::new(self, stellar_luminosity);
+    }
+
+    /// Moon is lit by the star from the heliocentric distance of its planet, while orbital period, rotation, tides and Hill sphere are set by the planet.
+    /// Planet's environment is derived first, moon follows its calendar.
+    pub fn derive_moon_environment(
+        &mut self,
+        planet_a: &f64,
+        planet_e: &f64,
+        planet_mass: &f64,
+        planet_year_days: &f64,
+        planet_seasons: &Seasons,
+        stellar_luminosity: &f64,
+        stellar_mass: &f64,
+        age: &f64,
+        ecosphere: &(f64, f64),
+        stellar_wind: &f64,
+        activity: &StellarActivity,
+        hydrodynamic_escape: &bool,
+        structure: &dyn StructureModel,
+        rng: &mut dyn RngCore,
+    ) {
+        self.distance_to_primary_star = *planet_a;
+        self.orbit_zone = orbital_zone(stellar_luminosity, self.distance_to_primary_star);
+        self.derive_environment(
+            planet_mass,
+            stellar_mass,
+            stellar_luminosity,
+            age,
+            ecosphere,
+            stellar_wind,
+            activity,
+            hydrodynamic_escape,
+            structure,
+            rng,
+        );
+        self.seasons = Seasons::of_moon(
+            self,
+            planet_seasons,
+            planet_e,
+            planet_year_days,
+            stellar_luminosity,
+        );
+    }
+
+    /// Tides of host body brake rotation over system age. Only synchronous rotation counts as tidal locking, other spin-orbit resonances as Mercury's 3:2 give resonant period.
+    pub fn despin(&mut self, host_mass: &f64, age: &f64, rng: &mut dyn RngCore) {
+        self.tides = TidalEvolution::new(self, host_mass, age, rng);
+        self.day_hours = self.tides.day_hours;
+        self.is_tidally_locked = self.tides.resonance == Some((1, 1));
+        self.resonant_period = self
+            .tides
+            .resonance
+            .is_some_and(|resonance| resonance != (1, 1));
+    }
+
+    /// Host mass is the body this one orbits, star for planets and planet for moons.
+    fn derive_environment(
+        &mut self,
+        host_mass: &f64,
+        stellar_mass: &f64,
+        stellar_luminosity: &f64,
+        age: &f64,
+        ecosphere: &(f64, f64),
+        stellar_wind: &f64,
+        activity: &StellarActivity,
+        hydrodynamic_escape: &bool,
+        structure: &dyn StructureModel,
+        rng: &mut dyn RngCore,
+    ) {
+        let flux = stellar_luminosity / self.distance_to_primary_star.powf(2.0);
+        let stellar_surface_temp =
+            stellar_surface_temp(stellar_radius_au(*stellar_mass), *stellar_luminosity);
+        (self.radius, self.density) = structure.radius_and_density(self, &ecosphere.1, rng);
+        self.xuv = XuvExposure::new(&self.distance_to_primary_star, activity);
+        self.escape = AtmosphericEscape::new(self, activity, hydrodynamic_escape);
+        // Given bodies report envelope loss but keep their mass
+        if self.escape.envelope_mass_lost > 0.0 && !self.is_protected {
+            let lost = self.escape.envelope_mass_lost / EARTH_MASSES_PER_SOLAR_MASS;
+            let envelope = (self.composition.gas * self.mass - lost).max(0.0);
+            self.mass -= lost;
+            self.composition = self.composition.with_gas(&(envelope / self.mass));
+            self.is_gas_giant = self.is_gas_giant && !self.escape.envelope_stripped;
+            (self.radius, self.density) = structure.radius_and_density(self, &ecosphere.1, rng);
         }
-        self.orbital_period_days = period(&self.a, &self.mass, stellar_mass);
-        self.day_hours = day_length(self, stellar_mass, main_seq_age);
-        self.axial_tilt = inclination(&self.a, rng);
+        let earth_masses = get_earth_mass(self.mass);
+        self.interior = Interior::new(self, age, host_mass);
+        self.orbital_period_days = period(&self.a, &self.mass, host_mass);
+        self.despin(host_mass, age, rng);
+        self.interior.dynamo = dynamo(self);
+        self.magnetosphere = Magnetosphere::new(self, stellar_wind);
+        self.obliquity = Obliquity::new(self, rng);
+        self.axial_tilt = self.obliquity.axial_tilt;
         self.escape_velocity = escape_vel(&self.mass, &self.radius);
         self.surface_accel = acceleration(&self.mass, &self.radius);
-        self.rms_velocity = rms_vel(&MOLECULAR_NITROGEN, &self.a);
-        self.molecule_weight = molecule_limit(&self.mass, &self.radius);
+        self.rms_velocity = rms_vel(&MOLECULAR_NITROGEN, &self.distance_to_primary_star);
+        self.molecule_weight = self.escape.smallest_retained_weight;
 
         if self.is_gas_giant {
             self.surface_grav = INCREDIBLY_LARGE_NUMBER;
-            self.greenhouse_effect = false;
             self.volatile_gas_inventory = INCREDIBLY_LARGE_NUMBER;
             self.surface_pressure_bar = INCREDIBLY_LARGE_NUMBER;
             self.boiling_point_kelvin = INCREDIBLY_LARGE_NUMBER;
             self.hydrosphere = INCREDIBLY_LARGE_NUMBER;
             self.albedo = about(GAS_GIANT_ALBEDO, 0.1, rng);
             self.surface_temp_kelvin = INCREDIBLY_LARGE_NUMBER;
+            self.climate_state = ClimateState::NoSurface;
         } else {
             self.surface_grav = gravity(&self.surface_accel);
-            self.greenhouse_effect = greenhouse(
-                &self.distance_to_primary_star,
-                &self.orbit_zone,
-                &self.surface_pressure_bar,
-                &ecosphere.1,
-            );
-            self.volatile_gas_inventory = vol_inventory(
-                &self.mass,
-                &self.escape_velocity,
-                &self.rms_velocity,
-                stellar_mass,
-                &self.orbit_zone,
-                &self.greenhouse_effect,
-                rng,
-            );
+            self.climate_state = climate_state(&flux, &stellar_surface_temp, &earth_masses, &0.0);
+            self.volatile_gas_inventory =
+                vol_inventory(
+                    &self.mass,
+                    &self.escape_velocity,
+                    &self.rms_velocity,
+                    stellar_mass,
+                    &self.orbit_zone,
+                    &self.climate_state,
+                    rng,
+                ) * self.magnetosphere.atmospheric_retention(&self.surface_grav)
+                    * self.escape.volatile_retention();
             self.surface_pressure_bar = pressure(
                 &self.volatile_gas_inventory,
                 &self.radius,
@@ -201,56 +368,29 @@ impl Planetesimal {
                 self.boiling_point_kelvin = 0.0;
             } else {
                 self.boiling_point_kelvin = boiling_point_kelvin(&self.surface_pressure_bar);
-                iterate_surface_temp(self, &ecosphere.1, rng);
+                self.surface_temp_converged = iterate_surface_temp(self, &ecosphere.1, rng);
             }
+            self.climate_state = climate_state(
+                &flux,
+                &stellar_surface_temp,
+                &earth_masses,
+                &self.surface_temp_kelvin,
+            );
         }
 
         get_day_night_temp_kelvin(self);
 
-        self.hill_sphere = hill_sphere_au(&self.a, &self.e, &self.mass, stellar_mass);
+        self.hill_sphere = hill_sphere_au(&self.a, &self.e, &self.mass, host_mass);
+        self.lagrange_points = lagrange_points(host_mass, &self.mass, &self.a);
         self.earth_masses = get_earth_mass(self.mass);
         self.earth_radii = float_to_precision(self.radius / EARTH_RADIUS_IN_KM);
         self.length_of_year = self.orbital_period_days / 365.25;
         self.escape_velocity_km_per_sec = float_to_precision(self.escape_velocity / CM_PER_KM);
-        self.is_tidally_locked = check_tidal_lock(self.day_hours, self.orbital_period_days);
-
-        // Probability of planet have a tectonic activity:
-        // 1) planet locked close to the star due to one side of planet being hotter than other
-        // 2) planet mass is within 0.5-1.5 of Earth mass
-        // 3) planet had collision
-        if (!self.is_gas_giant)
-            && (self.is_tidally_locked
-                || self.earth_masses > 0.5 && self.earth_masses < 1.5
-                || self.has_collision)
-        {
-            self.tectonic_activity = true;
-        }
 
-        // Probability of planet have a magnetosphere:
-        // 1) planet is gas giant
-        // 2) planet have tectonic activity
-        // 3) planet have atmosphere
-        if self.is_gas_giant || self.tectonic_activity || self.surface_pressure_bar > 0.0 {
-            self.magnetosphere = true;
-        }
-    }
-
-    pub fn random_outer_body(
-        planetesimal_inner_bound: &f64,
-        planetesimal_outer_bound: &f64,
-        rng: &mut dyn RngCore,
-    ) -> Self {
-        let mut random_body =
-            Planetesimal::new(planetesimal_inner_bound, planetesimal_outer_bound, rng);
-        random_body.mass = float_to_precision(rng.gen_range(PLANETESIMAL_MASS..PROTOPLANET_MASS * 1.0e5));
-        random_body.orbit_zone = 3;
-        random_body.radius = kothari_radius(
-            &random_body.mass,
-            &random_body.is_gas_giant,
-            &random_body.orbit_zone,
-        );
-
-        random_body
+        self.tectonic_activity = tectonic_activity(self);
+        self.habitability = Habitability::new(self);
+        self.longitude_of_periapsis = float_to_precision(rng.gen_range(0.0..360.0));
+        self.mean_anomaly = float_to_precision(rng.gen_range(0.0..360.0));
     }
 
     pub fn random_planet(
@@ -260,15 +400,26 @@ impl Planetesimal {
         e: f64,
         mass: f64,
         post_accretion_intensity: u32,
+        dust_density_coeff: f64,
+        stellar_wind: f64,
+        system_age: f64,
+        hydrodynamic_escape: bool,
+        habitable_zone_model: &HabitableZoneModel,
+        structure: &dyn StructureModel,
+        disk: &dyn DiskModel,
         rng: &mut dyn RngCore,
         #[cfg(events_log)]
         events_log: &mut AccreteEvents,
     ) -> Planetesimal {
-        let main_seq_age = main_sequence_age(stellar_mass, stellar_luminosity);
+        let age = system_age.min(main_sequence_age(stellar_mass, stellar_luminosity));
         let stellar_radius_au = stellar_radius_au(stellar_mass);
         let stellar_surface_temp = stellar_surface_temp(stellar_radius_au, stellar_luminosity);
         let spectral_class = spectral_class(&stellar_surface_temp);
-        let ecosphere = ecosphere(&stellar_luminosity, &spectral_class);
+        let habitable_zone = HabitableZone::new(&stellar_luminosity, &stellar_surface_temp);
+        let ecosphere =
+            habitable_zone_model.ecosphere(&habitable_zone, &stellar_luminosity, &spectral_class);
+        let activity = StellarActivity::new(&stellar_mass, &stellar_luminosity, &age);
+        let stellar_wind = stellar_wind * activity.wind_pressure;
 
         let mut is_gas_giant = false;
         let crit_mass = critical_limit(&B, &a, &e, &stellar_luminosity);
@@ -283,11 +434,19 @@ impl Planetesimal {
         }
         let id = random_id(rng);
         let b = semi_minor_axis(a, e);
+        let snow_line = disk.snow_line(&stellar_luminosity);
+        let gas = match is_gas_giant {
+            true => 1.0 - crit_mass / mass,
+            false => 0.0,
+        };
+        let composition = Composition::condensate(&a, &snow_line).with_gas(&gas);
 
         let mut random_planet = Planetesimal {
             a,
             b,
             e,
+            longitude_of_periapsis: 0.0,
+            mean_anomaly: 0.0,
             distance_to_primary_star: a,
             mass,
             earth_masses: 0.0,
@@ -299,13 +458,15 @@ impl Planetesimal {
             day_hours: 0.0,
             resonant_period: false,
             axial_tilt: 0.0,
+            obliquity: Obliquity::default(),
             escape_velocity: 0.0,
             surface_accel: 0.0,
             surface_grav: 0.0,
             rms_velocity: 0.0,
             molecule_weight: 0.0,
             volatile_gas_inventory: 0.0,
-            greenhouse_effect: false,
+            climate_state: ClimateState::Frozen,
+            surface_temp_converged: true,
             albedo: 0.0,
             surface_temp_kelvin: 0.0,
             day_temp_kelvin: 0.0,
@@ -323,21 +484,51 @@ impl Planetesimal {
             escape_velocity_km_per_sec: 0.0,
             is_tidally_locked: false,
             is_moon: false,
+            is_regular_moon: false,
+            is_shepherd_moon: false,
+            is_pinned: false,
+            is_protected: false,
+            is_known: false,
             is_gas_giant,
             is_dwarf_planet,
             orbit_clearing,
             hill_sphere: 0.0,
+            lagrange_points: LagrangePoints::default(),
+            trojans: Vec::new(),
             tectonic_activity: false,
-            magnetosphere: false,
+            magnetosphere: Magnetosphere::default(),
+            xuv: XuvExposure::default(),
+            escape: AtmosphericEscape::default(),
             has_collision: false,
+            composition,
+            interior: Interior::default(),
+            tides: TidalEvolution::default(),
+            seasons: Seasons::default(),
+            climate: Climate::default(),
+            habitability: Habitability::default(),
             id,
         };
 
+        // Lone planet plays the outermost giant that scatters the reservoir towards itself
+        let mut cometary_reservoir = CometaryReservoir::new(
+            &[random_planet.clone()],
+            disk,
+            &dust_density_coeff,
+            &stellar_mass,
+            &snow_line,
+            &0.0,
+            rng,
+        );
+        let impactor_mass = cometary_reservoir.impact_mass / post_accretion_intensity as f64;
         for _i in 0..post_accretion_intensity {
             let Planetesimal { a, e, mass, .. } = random_planet;
             let r_inner = inner_effect_limit(&a, &e, &mass);
             let r_outer = outer_effect_limit(&a, &e, &mass);
-            let mut outer_body = Planetesimal::random_outer_body(&r_inner, &r_outer, rng);
+            let Some(mut outer_body) =
+                cometary_reservoir.scatter_impactor(&impactor_mass, &r_inner, &r_outer, rng)
+            else {
+                break;
+            };
             planetesimals_intersect(
                 &mut outer_body,
                 &mut random_planet,
@@ -352,20 +543,43 @@ impl Planetesimal {
         random_planet.derive_planetary_environment(
             &stellar_luminosity,
             &stellar_mass,
-            &main_seq_age,
+            &age,
             &ecosphere,
+            &stellar_wind,
+            &activity,
+            &hydrodynamic_escape,
+            structure,
             rng,
         );
 
-        for moon in random_planet.moons.iter_mut() {
-            moon.derive_planetary_environment(
+        let Planetesimal {
+            a: planet_a,
+            e: planet_e,
+            mass: planet_mass,
+            orbital_period_days,
+            seasons,
+            moons,
+            ..
+        } = &mut random_planet;
+        for moon in moons.iter_mut() {
+            moon.derive_moon_environment(
+                planet_a,
+                planet_e,
+                planet_mass,
+                orbital_period_days,
+                seasons,
                 &stellar_luminosity,
-                &random_planet.mass,
-                &main_seq_age,
+                &stellar_mass,
+                &age,
                 &ecosphere,
+                &stellar_wind,
+                &activity,
+                &hydrodynamic_escape,
+                structure,
                 rng,
             );
         }
+        sculpt_rings(&mut random_planet, rng);
 
         random_planet
     }
@@ -1,4 +1,7 @@
+use crate::activity::StellarActivity;
 use crate::consts::*;
+use crate::habitable_zone::*;
+use crate::spectrum::*;
 
 use serde::{Deserialize, Serialize};
 
@@ -10,36 +13,64 @@ pub struct PrimaryStar {
     pub stellar_radius_au: f64,
     pub spectral_class: SpectralClass,
     pub bv_color_index: f64,
+    // gamma-encoded sRGB of blackbody at surface temperature
     pub color: [f64; 3],
+    pub blackbody_color: BlackbodyColor,
     pub main_seq_age: f64,
+    // system age, years, no longer than main sequence lifetime
+    pub age: f64,
+    // inner and outer radius of habitable zone used for planetary temperatures, AU
     pub ecosphere: (f64, f64),
+    pub habitable_zone: HabitableZone,
+    // stellar wind dynamic pressure at 1 AU relative to the present Sun
+    pub stellar_wind: f64,
+    // rotation, X-ray and EUV output, flares and wind at system age
+    pub activity: StellarActivity,
 }
 
 impl PrimaryStar {
-    pub fn new(stellar_mass: f64) -> Self {
+    /// Main sequence star of given mass (solar masses) and system age (years).
+    pub fn new(stellar_mass: f64, system_age: f64) -> Self {
         // Warning - stallar mass higher than 10 solar masses probably refer to non-regular and supermassive stars (short lived giants, black holes, wolf-rayet stars, etc..) and require other calculation methods
         // Possibly can be useful https://www.fabiopacucci.com/resources/black-hole-calculator/formulas-black-hole-calculator/
         let stellar_luminosity = luminosity(stellar_mass);
         let main_seq_age = main_sequence_age(stellar_mass, stellar_luminosity);
+        let age = system_age.min(main_seq_age);
         let stellar_radius_au = stellar_radius_au(stellar_mass);
         let stellar_surface_temp = stellar_surface_temp(stellar_radius_au, stellar_luminosity);
         let spectral_class = spectral_class(&stellar_surface_temp);
         let bv_color_index = bv_color_index(stellar_surface_temp);
-        let color = bv_to_rgb(bv_color_index);
-        let ecosphere = ecosphere(&stellar_luminosity, &spectral_class);
+        let blackbody_color = BlackbodyColor::new(&stellar_surface_temp);
+        let habitable_zone = HabitableZone::new(&stellar_luminosity, &stellar_surface_temp);
+        let ecosphere = HabitableZoneModel::default().ecosphere(
+            &habitable_zone,
+            &stellar_luminosity,
+            &spectral_class,
+        );
+        let activity = StellarActivity::new(&stellar_mass, &stellar_luminosity, &age);
 
         Self {
             stellar_mass,
             stellar_luminosity,
             main_seq_age,
+            age,
             ecosphere,
+            habitable_zone,
             stellar_surface_temp,
             stellar_radius_au,
             spectral_class,
             bv_color_index,
-            color,
+            color: blackbody_color.srgb,
+            blackbody_color,
+            stellar_wind: activity.wind_pressure,
+            activity,
         }
     }
+
+    /// Blackbody spectrum of the star for renderers, evenly sampled over wavelength range in nm with radiance relative to its highest sample.
+    pub fn spectrum(&self, wavelength_range: &(f64, f64), samples: &usize) -> Vec<(f64, f64)> {
+        blackbody_spectrum(&self.stellar_surface_temp, wavelength_range, samples)
+    }
 }
 
 /// Approximated luminosity from mass
@@ -47,11 +78,11 @@ pub fn luminosity(mass: f64) -> f64 {
     mass.powf(3.5)
 }
 
-/// Star min-max habitable zone
+/// Legacy star min-max habitable zone from flux factors per spectral class, none for classes without them.
 /// [Normalized solar flux factor](http://www.solstation.com/habitable.html)
 /// [Red dwarfs habitable zone 1](https://en.wikipedia.org/wiki/Habitability_of_red_dwarf_systems)
 /// [Planetary_habitability](https://en.wikipedia.org/wiki/Planetary_habitability)
-pub fn ecosphere(luminosity: &f64, spectral_class: &SpectralClass) -> (f64, f64) {
+pub fn ecosphere(luminosity: &f64, spectral_class: &SpectralClass) -> Option<(f64, f64)> {
     let (outer_normalized_flux_factor, inner_normalized_flux_factor) = match spectral_class {
         // BrownDwarfs. For Y, L , T approzimation is used
         SpectralClass::Rogue => (0.0, 0.0),
@@ -69,9 +100,12 @@ pub fn ecosphere(luminosity: &f64, spectral_class: &SpectralClass) -> (f64, f64)
         SpectralClass::B => (0.0, 0.0),
         SpectralClass::O => (0.0, 0.0),
     };
+    if outer_normalized_flux_factor == 0.0 {
+        return None;
+    }
     let min_ecosphere_radius = (luminosity / inner_normalized_flux_factor).sqrt();
     let max_ecosphere_radius = (luminosity / outer_normalized_flux_factor).sqrt();
-    (min_ecosphere_radius, max_ecosphere_radius)
+    Some((min_ecosphere_radius, max_ecosphere_radius))
 }
 
 /// Main sequence star age
@@ -99,24 +133,16 @@ pub fn bv_color_index(stellar_surface_temp: f64) -> f64 {
     (5601.0 / stellar_surface_temp).powf(1.5) - 0.4
 }
 
-/// Star RGB color from color index
+/// Rough star RGB color from color index, see BlackbodyColor for color integrated from spectrum
 /// [Reference table](http://www.vendian.org/mncharity/dir3/starcolor/details.html)
 /// [StackOverflow](https://stackoverflow.com/questions/21977786/star-b-v-color-index-to-apparent-rgb-color)
 pub fn bv_to_rgb(bv: f64) -> [f64; 3] {
     let mut r = 0.0;
     let mut g = 0.0;
     let mut b = 0.0;
-    let mut bv = bv;
+    let bv = bv.clamp(-0.4, 2.0);
     let mut t;
 
-    if bv < -0.4 {
-        bv = -0.4;
-    }
-
-    if bv > 2.0 {
-        bv = 2.0;
-    }
-
     if (-0.40..0.00).contains(&bv) {
         t = (bv + 0.40) / 0.40;
         r = 0.61 + (0.11 * t) + (0.1 * t * t);
@@ -188,9 +214,26 @@ mod tests {
         assert_eq!(0.6, bv_sun);
     }
 
-    // #[test]
-    // fn check_sun_color() {
-    //     let color_sun = bv_to_rgb(0.6);
-    //     assert_eq!("#fff3ea", color_sun);
-    // }
+    #[test]
+    fn check_sun_color() {
+        let color_sun = BlackbodyColor::new(&SUN_EFFECTIVE_TEMP);
+        // Planckian locus at solar effective temperature
+        assert!((color_sun.chromaticity.0 - 0.3264).abs() < 0.002);
+        assert!((color_sun.chromaticity.1 - 0.3360).abs() < 0.002);
+        // #fff3ea from blackbody color table
+        let expected = [255.0, 243.0, 234.0];
+        for (channel, expected) in color_sun.srgb.iter().zip(expected) {
+            assert!((channel * 255.0 - expected).abs() <= 3.0);
+        }
+        assert_eq!(color_sun.hex().len(), 7);
+    }
+
+    #[test]
+    fn activity_at_system_age() {
+        let red_dwarf = PrimaryStar::new(0.3, SOLAR_AGE);
+        assert_eq!(red_dwarf.age, SOLAR_AGE);
+        assert_eq!(red_dwarf.activity.age, SOLAR_AGE);
+        let a_star = PrimaryStar::new(2.0, SOLAR_AGE);
+        assert_eq!(a_star.age, a_star.main_seq_age);
+    }
 }
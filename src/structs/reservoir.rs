@@ -0,0 +1,345 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::asteroid_belt::dust_mass;
+use crate::structs::composition::Composition;
+use crate::structs::disk::DiskModel;
+use crate::structs::planetesimal::Planetesimal;
+use crate::utils::*;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Cumulative size distribution index of collisionally evolved population, N(>R) ~ R^-2.5.
+/// [Dohnanyi 1969, Collisional model of asteroids and their debris](https://doi.org/10.1029/JB074i010p02531)
+const SIZE_INDEX: f64 = 2.5;
+
+/// Share of the primordial Kuiper belt that survives scattering by migrating giants, the rest is lost within a few hundred million years.
+const KUIPER_SURVIVING_FRACTION: f64 = 0.01;
+
+/// Share of planetesimals scattered by giant planets that ends in the Oort cloud, most are ejected from the system.
+const OORT_EFFICIENCY: f64 = 0.05;
+
+/// Share of Kuiper belt bodies larger than 1 km that are active short-period comets at any time, about 500 Jupiter-family comets for the Solar System.
+const SHORT_PERIOD_COMET_FRACTION: f64 = 1.0e-6;
+
+/// Number of short-period comets generated with full orbits.
+const SHORT_PERIOD_COMETS_SAMPLE: usize = 10;
+
+/// Share of planetesimals scattered by giant planets that reaches the inner system and hits planets, the rest is ejected or ends in the Oort cloud.
+/// About 0.03 Earth masses out of the 30 scattered by Neptune during the late heavy bombardment.
+/// [Gomes et al. 2005, Origin of the cataclysmic Late Heavy Bombardment period of the terrestrial planets](https://doi.org/10.1038/nature03676)
+const IMPACT_MASS_FRACTION: f64 = 1.0e-3;
+
+/// Population of small icy bodies, with cumulative size distribution N(>R) = number * (R / 1 km)^-size_index.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SmallBodyPopulation {
+    // units of AU
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    // units of solar masses
+    pub mass: f64,
+    // bodies larger than 1 km
+    pub number: f64,
+    // bodies larger than 1 km per AU3
+    pub number_density: f64,
+    pub size_index: f64,
+    // radius of the largest expected body, km
+    pub largest_radius: f64,
+}
+
+/// Outer reservoir of icy bodies: Kuiper belt beyond the outermost giant planet and Oort cloud of bodies scattered by giants.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CometaryReservoir {
+    pub kuiper_belt: SmallBodyPopulation,
+    pub oort_cloud: SmallBodyPopulation,
+    // bodies scattered from primordial Kuiper belt, source of impactors
+    pub scattered_disk: SmallBodyPopulation,
+    // active short-period comets, up to ten are generated
+    pub short_period_comets: Vec<Planetesimal>,
+    // units of solar masses, part of planetesimals scattered by giants left to hit planets during late heavy bombardment
+    pub impact_mass: f64,
+}
+
+impl SmallBodyPopulation {
+    /// Population of given mass (solar masses) and bulk density (g/cc) spread over volume in AU3.
+    pub fn new(
+        inner_radius: f64,
+        outer_radius: f64,
+        mass: f64,
+        bulk_density: f64,
+        volume: f64,
+    ) -> Self {
+        let number = population_number(&mass, &bulk_density, &SIZE_INDEX);
+        let number_density = match volume > 0.0 {
+            true => number / volume,
+            false => 0.0,
+        };
+
+        Self {
+            inner_radius,
+            outer_radius,
+            mass,
+            number,
+            number_density,
+            size_index: SIZE_INDEX,
+            largest_radius: number.powf(1.0 / SIZE_INDEX),
+        }
+    }
+
+    /// Population of given number of bodies larger than 1 km.
+    pub fn from_number(
+        inner_radius: f64,
+        outer_radius: f64,
+        number: f64,
+        bulk_density: f64,
+        volume: f64,
+    ) -> Self {
+        let mass = population_mass(&number, &bulk_density, &SIZE_INDEX);
+        Self::new(inner_radius, outer_radius, mass, bulk_density, volume)
+    }
+
+    /// Number of bodies larger than radius in km.
+    pub fn number_larger_than(&self, radius: &f64) -> f64 {
+        self.number * radius.powf(-self.size_index)
+    }
+
+    /// Radius (km) of random body larger than 1 km drawn from size distribution, limited by the largest body.
+    pub fn random_radius(&self, rng: &mut dyn RngCore) -> f64 {
+        let u: f64 = rng.gen_range(0.0..1.0);
+        (1.0 - u)
+            .powf(-1.0 / self.size_index)
+            .min(self.largest_radius.max(1.0))
+    }
+
+    /// Takes mass (solar masses) out of population, number of bodies drops in proportion.
+    pub fn remove_mass(&mut self, mass: &f64) {
+        let remaining = match self.mass > 0.0 {
+            true => (1.0 - mass / self.mass).max(0.0),
+            false => 0.0,
+        };
+        self.mass *= remaining;
+        self.number *= remaining;
+        self.number_density *= remaining;
+    }
+}
+
+impl CometaryReservoir {
+    /// Reservoir sculpted by giant planets, outermost planet plays their role in systems without giants.
+    /// Kuiper belt spans from 3:2 to beyond 2:1 resonance with the outermost giant, as the classical and resonant belt around Neptune.
+    /// Oort cloud extends to galactic tidal radius and is fed by planetesimals scattered from the giants' zone, from the primordial Kuiper belt and by protected pinned planets (scattered mass in solar masses).
+    pub fn new(
+        planets: &[Planetesimal],
+        disk: &dyn DiskModel,
+        dust_density_coeff: &f64,
+        stellar_mass: &f64,
+        snow_line: &f64,
+        scattered_mass: &f64,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let giants: Vec<&Planetesimal> = planets.iter().filter(|p| p.is_gas_giant).collect();
+        let outermost = match giants.last().copied().or(planets.last()) {
+            Some(p) => p,
+            None => return Self::default(),
+        };
+        let dust_mass = |inner_radius: f64, outer_radius: f64| {
+            dust_mass(
+                disk,
+                dust_density_coeff,
+                stellar_mass,
+                &inner_radius,
+                &outer_radius,
+                snow_line,
+            )
+            .0
+        };
+
+        let kuiper_inner = outermost.a * 1.5_f64.powf(2.0 / 3.0);
+        let kuiper_outer = outermost.a * 2.0_f64.powf(2.0 / 3.0) * 1.1;
+        let primordial_kuiper_mass = dust_mass(kuiper_inner, kuiper_outer);
+        // Flared disk with half-thickness of a tenth of radius, about 6° of inclination
+        let kuiper_volume = 0.4 * PI * (kuiper_outer.powf(3.0) - kuiper_inner.powf(3.0)) / 3.0;
+        let kuiper_belt = SmallBodyPopulation::new(
+            kuiper_inner,
+            kuiper_outer,
+            primordial_kuiper_mass * KUIPER_SURVIVING_FRACTION,
+            1.0,
+            kuiper_volume,
+        );
+
+        let scattered_kuiper_mass = primordial_kuiper_mass * (1.0 - KUIPER_SURVIVING_FRACTION);
+        let giants_zone_mass = match giants.first() {
+            Some(innermost_giant) => dust_mass(innermost_giant.a, kuiper_inner),
+            None => 0.0,
+        };
+        let oort_inner = 2.0e3 * stellar_mass.powf(1.0 / 3.0);
+        let oort_outer = 1.0e5 * stellar_mass.powf(1.0 / 3.0);
+        let oort_volume = 4.0 * PI * (oort_outer.powf(3.0) - oort_inner.powf(3.0)) / 3.0;
+        let oort_cloud = SmallBodyPopulation::new(
+            oort_inner,
+            oort_outer,
+            (giants_zone_mass + scattered_kuiper_mass + scattered_mass) * OORT_EFFICIENCY,
+            0.6,
+            oort_volume,
+        );
+
+        let scattered_disk =
+            SmallBodyPopulation::new(kuiper_inner, kuiper_outer, scattered_kuiper_mass, 1.0, 0.0);
+        let impact_mass =
+            (giants_zone_mass + scattered_kuiper_mass + scattered_mass) * IMPACT_MASS_FRACTION;
+
+        let short_period_comets = match giants.first() {
+            Some(innermost_giant) => {
+                let count = (kuiper_belt.number * SHORT_PERIOD_COMET_FRACTION).round() as usize;
+                (0..count.min(SHORT_PERIOD_COMETS_SAMPLE))
+                    .map(|_| short_period_comet(&innermost_giant.a, &kuiper_belt, rng))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        Self {
+            kuiper_belt,
+            oort_cloud,
+            scattered_disk,
+            short_period_comets,
+            impact_mass,
+        }
+    }
+
+    /// Scattered body of given mass (solar masses) on its way to hit planet, perihelion falls between given limits (AU) and aphelion stays in the Kuiper belt region.
+    /// Its mass is taken out of the impact mass and the scattered disk, None once the impact mass is spent.
+    pub fn scatter_impactor(
+        &mut self,
+        mass: &f64,
+        perihelion_min: &f64,
+        perihelion_max: &f64,
+        rng: &mut dyn RngCore,
+    ) -> Option<Planetesimal> {
+        if self.impact_mass <= 0.0 {
+            return None;
+        }
+        let Self {
+            scattered_disk,
+            impact_mass,
+            ..
+        } = self;
+        let mass = mass.min(*impact_mass);
+        let perihelion = rng.gen_range(*perihelion_min..=*perihelion_max);
+        let aphelion = rng
+            .gen_range(scattered_disk.inner_radius..=scattered_disk.outer_radius)
+            .max(perihelion);
+        let density = 1.0;
+        let radius = volume_radius(&mass, &density);
+        scattered_disk.remove_mass(&mass);
+        *impact_mass -= mass;
+
+        let a = (aphelion + perihelion) / 2.0;
+        let e = (aphelion - perihelion) / (aphelion + perihelion);
+        let mut impactor = Planetesimal::on_orbit(a, e, random_id(rng));
+        impactor.mass = mass;
+        impactor.radius = radius;
+        impactor.density = density;
+        impactor.orbit_zone = 3;
+        impactor.composition = Composition::icy();
+        Some(impactor)
+    }
+}
+
+/// Jupiter-family comet handed inwards by giants, its aphelion stays close to the innermost giant that controls it.
+pub fn short_period_comet(
+    giant_a: &f64,
+    source: &SmallBodyPopulation,
+    rng: &mut dyn RngCore,
+) -> Planetesimal {
+    let aphelion = giant_a * rng.gen_range(0.9..1.2);
+    let perihelion = giant_a * rng.gen_range(0.15..0.5);
+    let a = (aphelion + perihelion) / 2.0;
+    let e = (aphelion - perihelion) / (aphelion + perihelion);
+    let radius = source.random_radius(rng);
+    let density = 0.6;
+
+    let mut comet = Planetesimal::new(&perihelion, &aphelion, rng);
+    comet.a = a;
+    comet.e = e;
+    comet.b = semi_minor_axis(a, e);
+    comet.distance_to_primary_star = a;
+    comet.mass = 4.0 / 3.0 * PI * (radius * CM_PER_KM).powf(3.0) * density / SOLAR_MASS_IN_GRAMS;
+    comet.radius = radius;
+    comet.density = density;
+    comet.orbit_zone = 3;
+    comet.composition = Composition::icy();
+    comet
+}
+
+/// Mass (solar masses) of population with given number of bodies larger than 1 km, bulk density (g/cc) and cumulative size index below 3.
+/// Largest body is the one expected once, R_max = N^(1/q), so mass grows as N * (R_max^(3-q) - 1).
+pub fn population_mass(number: &f64, bulk_density: &f64, size_index: &f64) -> f64 {
+    if *number <= 1.0 {
+        return 0.0;
+    }
+    let km_body_mass = 4.0 / 3.0 * PI * CM_PER_KM.powf(3.0) * bulk_density;
+    number * km_body_mass * size_index / (3.0 - size_index)
+        * (number.powf((3.0 - size_index) / size_index) - 1.0)
+        / SOLAR_MASS_IN_GRAMS
+}
+
+/// Number of bodies larger than 1 km in population of given mass, inverse of population_mass found by bisection.
+pub fn population_number(mass: &f64, bulk_density: &f64, size_index: &f64) -> f64 {
+    if *mass <= 0.0 {
+        return 0.0;
+    }
+    let (mut low, mut high) = (0.0_f64, 100.0_f64);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        match population_mass(&mid.exp(), bulk_density, size_index) < *mass {
+            true => low = mid,
+            false => high = mid,
+        }
+    }
+    low.exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn kuiper_belt_population() {
+        // Present Kuiper belt, about 0.03 Earth masses
+        let mass = 0.03 / EARTH_MASSES_PER_SOLAR_MASS;
+        let kuiper_belt = SmallBodyPopulation::new(39.0, 48.0, mass, 1.0, 1.0);
+        assert!(kuiper_belt.number > 1.0e8 && kuiper_belt.number < 1.0e9);
+        assert!(kuiper_belt.largest_radius > 1000.0 && kuiper_belt.largest_radius < 3000.0);
+        assert!(kuiper_belt.number_larger_than(&100.0) < kuiper_belt.number * 1.0e-4);
+    }
+
+    #[test]
+    fn impactors_deplete_reservoir() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mass = 20.0 / EARTH_MASSES_PER_SOLAR_MASS;
+        let impact_mass = mass * IMPACT_MASS_FRACTION;
+        let mut reservoir = CometaryReservoir {
+            scattered_disk: SmallBodyPopulation::new(20.0, 35.0, mass, 1.0, 0.0),
+            impact_mass,
+            ..CometaryReservoir::default()
+        };
+        let impactor_mass = impact_mass * 0.6;
+        let impactor = reservoir
+            .scatter_impactor(&impactor_mass, &0.9, &1.1, &mut rng)
+            .unwrap();
+        assert!(
+            impactor.mass == impactor_mass && perihelion_distance(&impactor.a, &impactor.e) < 1.1
+        );
+        assert!((reservoir.scattered_disk.mass - (mass - impactor_mass)).abs() < 1.0e-20);
+        // Last impactor carries what is left of the impact mass
+        let impactor = reservoir
+            .scatter_impactor(&impactor_mass, &0.9, &1.1, &mut rng)
+            .unwrap();
+        assert!((impactor.mass - impact_mass * 0.4).abs() < 1.0e-20);
+        assert!(reservoir
+            .scatter_impactor(&impactor_mass, &0.9, &1.1, &mut rng)
+            .is_none());
+    }
+}
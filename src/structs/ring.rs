@@ -1,32 +1,307 @@
-use crate::{structs::Planetesimal, utils::random_id};
+use crate::consts::*;
+use crate::{structs::Planetesimal, utils::*};
 
-use rand::RngCore;
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 
+/// Material of ring particles.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RingComposition {
+    Icy,
+    Rocky,
+}
+
+/// Continuous part of a ring between two gaps.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Ringlet {
+    // units of km from planet center
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    // units of solar masses
+    pub mass: f64,
+    // normal optical depth, above 1.0 ringlet is opaque
+    pub optical_depth: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Ring {
-    pub a: f64,
-    pub mass: f64,
+    // units of km from planet center
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    // units of km
     pub width: f64,
+    // units of solar masses
+    pub mass: f64,
+    pub composition: RingComposition,
+    pub albedo: f64,
+    pub ringlets: Vec<Ringlet>,
     pub id: String,
 }
 
+/// Resonances with exterior moons that clear gaps, as particle orbits per moon orbits (Mimas 2:1 clears the Cassini Division).
+const RING_RESONANCES: [(f64, f64); 3] = [(2.0, 1.0), (3.0, 2.0), (3.0, 1.0)];
+
 impl Ring {
-    pub fn from_planet(roche_limit: f64, moon: &Planetesimal) -> Self {
-        Ring {
-            a: roche_limit,
+    /// Moon torn apart inside Roche limit spreads between the limit and the planet's surface.
+    pub fn from_moon(planet: &Planetesimal, moon: &Planetesimal, roche_limit: f64) -> Self {
+        let roche_limit = roche_limit * KM_PER_AU;
+        let outer_radius = roche_limit.max(planet.radius * 1.2);
+        let inner_radius = (roche_limit * 0.5).max(planet.radius * 1.1);
+        let composition = match moon.orbit_zone {
+            1 => RingComposition::Rocky,
+            _ => RingComposition::Icy,
+        };
+        let mut ring = Ring {
+            inner_radius,
+            outer_radius,
+            width: 0.0,
             mass: moon.mass,
-            width: moon.radius * 2.0,
+            composition,
+            albedo: ring_albedo(&composition),
+            ringlets: Vec::new(),
             id: moon.id.clone(),
-        }
+        };
+        ring.set_ringlets(vec![(inner_radius, outer_radius, 1.0)]);
+        ring
     }
 
-    pub fn new(a: f64, mass: f64, width: f64, rng: &mut dyn RngCore) -> Self {
-        Ring {
-            a,
+    pub fn new(
+        inner_radius: f64,
+        outer_radius: f64,
+        mass: f64,
+        composition: RingComposition,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let mut ring = Ring {
+            inner_radius,
+            outer_radius,
+            width: 0.0,
             mass,
-            width,
+            composition,
+            albedo: ring_albedo(&composition),
+            ringlets: Vec::new(),
             id: random_id(rng),
+        };
+        ring.set_ringlets(vec![(inner_radius, outer_radius, 1.0)]);
+        ring
+    }
+
+    /// Splits ring mass between ringlets given as edges and relative surface density.
+    fn set_ringlets(&mut self, edges: Vec<(f64, f64, f64)>) {
+        let weights: f64 = edges
+            .iter()
+            .map(|(inner, outer, density)| (outer.powf(2.0) - inner.powf(2.0)) * density)
+            .sum();
+        let particle_density = match self.composition {
+            RingComposition::Icy => 0.9,
+            RingComposition::Rocky => 2.5,
+        };
+        self.ringlets = edges
+            .iter()
+            .map(|(inner_radius, outer_radius, density)| {
+                let area_km2 = PI * (outer_radius.powf(2.0) - inner_radius.powf(2.0));
+                let mass = match weights > 0.0 {
+                    true => self.mass * area_km2 * density / (PI * weights),
+                    false => 0.0,
+                };
+                let surface_density = mass * SOLAR_MASS_IN_GRAMS / (area_km2 * CM_PER_KM.powf(2.0));
+                Ringlet {
+                    inner_radius: *inner_radius,
+                    outer_radius: *outer_radius,
+                    mass,
+                    optical_depth: optical_depth(&surface_density, &particle_density),
+                }
+            })
+            .collect();
+        if let (Some(first), Some(last)) = (self.ringlets.first(), self.ringlets.last()) {
+            self.inner_radius = first.inner_radius;
+            self.outer_radius = last.outer_radius;
+        }
+        self.width = self.outer_radius - self.inner_radius;
+    }
+
+    /// Adds debris of other ring, composition follows the more massive one.
+    pub fn merge(&mut self, other: &Ring) {
+        if other.mass > self.mass {
+            self.composition = other.composition;
+            self.albedo = other.albedo;
+        }
+        self.mass += other.mass;
+        let inner_radius = self.inner_radius.min(other.inner_radius);
+        let outer_radius = self.outer_radius.max(other.outer_radius);
+        self.set_ringlets(vec![(inner_radius, outer_radius, 1.0)]);
+    }
+
+    /// Opens gaps at resonances with exterior moons and lets surface density vary between ringlets.
+    pub fn open_gaps(&mut self, planet_mass: &f64, moons: &[Planetesimal], rng: &mut dyn RngCore) {
+        let mut edges = vec![(self.inner_radius, self.outer_radius)];
+        for moon in moons.iter() {
+            let moon_a = moon.a * KM_PER_AU;
+            let mass_ratio = moon.mass / planet_mass;
+            for (particle_orbits, moon_orbits) in RING_RESONANCES {
+                let resonance = moon_a * (moon_orbits / particle_orbits).powf(2.0 / 3.0);
+                let gap_width = resonance_gap_width(&mass_ratio);
+                // Narrow gaps are closed by viscous spreading of particles
+                if gap_width < 0.001 {
+                    continue;
+                }
+                let half_width = resonance * gap_width / 2.0;
+                edges = cut_gap(&edges, resonance - half_width, resonance + half_width);
+            }
         }
+        // Ring confined between resonances is cleared entirely
+        if edges.is_empty() {
+            self.ringlets.clear();
+            self.width = 0.0;
+            return;
+        }
+        let ringlets = edges
+            .iter()
+            .map(|(inner, outer)| (*inner, *outer, rng.gen_range(0.2..1.0)))
+            .collect();
+        self.set_ringlets(ringlets);
+    }
+
+    /// Moon is a shepherd when it orbits close outside the ring edge or inside one of its gaps, like Prometheus and Pan in Saturn's rings.
+    pub fn is_shepherded_by(&self, moon: &Planetesimal) -> bool {
+        let moon_a = moon.a * KM_PER_AU;
+        let near_edge = moon_a > self.outer_radius && moon_a <= self.outer_radius * 1.25;
+        let in_gap = moon_a > self.inner_radius
+            && moon_a < self.outer_radius
+            && !self
+                .ringlets
+                .iter()
+                .any(|r| moon_a >= r.inner_radius && moon_a <= r.outer_radius);
+        near_edge || in_gap
+    }
+}
+
+/// Icy particles are bright as in Saturn's rings, rocky and dusty ones dark as in Jupiter's.
+pub fn ring_albedo(composition: &RingComposition) -> f64 {
+    match composition {
+        RingComposition::Icy => 0.5,
+        RingComposition::Rocky => 0.05,
+    }
+}
+
+/// Normal optical depth of particles with typical radius of half meter, surface density in g/cm2 and particle density in g/cc.
+pub fn optical_depth(surface_density: &f64, particle_density: &f64) -> f64 {
+    let particle_radius_cm = 50.0;
+    3.0 * surface_density / (4.0 * particle_density * particle_radius_cm)
+}
+
+/// Share of resonance radius cleared by moon, scaled to the Cassini Division opened by Mimas. Mass ratio is moon to planet.
+pub fn resonance_gap_width(mass_ratio: &f64) -> f64 {
+    (10.0 * mass_ratio.powf(1.0 / 3.0)).min(0.1)
+}
+
+/// Removes span between gap edges from ringlets, keeping ringlets sorted from planet outwards.
+fn cut_gap(edges: &[(f64, f64)], gap_inner: f64, gap_outer: f64) -> Vec<(f64, f64)> {
+    let mut next_edges = Vec::new();
+    for (inner, outer) in edges.iter() {
+        if gap_outer <= *inner || gap_inner >= *outer {
+            next_edges.push((*inner, *outer));
+            continue;
+        }
+        if gap_inner > *inner {
+            next_edges.push((*inner, gap_inner));
+        }
+        if gap_outer < *outer {
+            next_edges.push((gap_outer, *outer));
+        }
+    }
+    next_edges
+}
+
+/// Shapes rings of a planet by its final radius and moons, and flags shepherd moons.
+/// Debris of several disrupted moons spreading over the same span joins into one ring.
+pub fn sculpt_rings(planet: &mut Planetesimal, rng: &mut dyn RngCore) {
+    let Planetesimal {
+        mass,
+        radius,
+        rings,
+        moons,
+        ..
+    } = planet;
+    let surface = *radius * 1.1;
+    rings.retain(|ring| ring.outer_radius > surface);
+    rings.sort_by(|r1, r2| r1.inner_radius.total_cmp(&r2.inner_radius));
+
+    let mut merged_rings: Vec<Ring> = Vec::new();
+    for ring in rings.drain(..) {
+        match merged_rings.last_mut() {
+            Some(last) if ring.inner_radius <= last.outer_radius => last.merge(&ring),
+            _ => merged_rings.push(ring),
+        }
+    }
+    for ring in merged_rings.iter_mut() {
+        ring.inner_radius = ring.inner_radius.max(surface);
+        ring.open_gaps(mass, moons, rng);
+    }
+    merged_rings.retain(|ring| !ring.ringlets.is_empty());
+    *rings = merged_rings;
+
+    for moon in moons.iter_mut() {
+        moon.is_shepherd_moon = rings.iter().any(|ring| ring.is_shepherded_by(moon));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn gap_splits_ringlet() {
+        let edges = cut_gap(&[(100.0, 200.0)], 140.0, 150.0);
+        assert_eq!(edges, vec![(100.0, 140.0), (150.0, 200.0)]);
+        let edges = cut_gap(&edges, 190.0, 250.0);
+        assert_eq!(edges, vec![(100.0, 140.0), (150.0, 190.0)]);
+    }
+
+    #[test]
+    fn merged_ring_spans_both() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut ring = Ring::new(100.0, 150.0, 1.0e-12, RingComposition::Icy, &mut rng);
+        ring.merge(&Ring::new(
+            140.0,
+            200.0,
+            2.0e-12,
+            RingComposition::Rocky,
+            &mut rng,
+        ));
+        assert_eq!(ring.composition, RingComposition::Rocky);
+        assert_eq!(ring.ringlets.len(), 1);
+        assert_eq!(
+            (ring.ringlets[0].inner_radius, ring.ringlets[0].outer_radius),
+            (100.0, 200.0)
+        );
+        assert!((ring.ringlets[0].mass - 3.0e-12).abs() < 1.0e-24);
+    }
+
+    #[test]
+    fn cleared_ring_has_no_ringlets() {
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let mut ring = Ring::new(
+            100_000.0,
+            101_000.0,
+            1.0e-12,
+            RingComposition::Icy,
+            &mut rng,
+        );
+        // Moon massive enough to open gaps of the widest span
+        let moon_a = 100_500.0 * 2.0_f64.powf(2.0 / 3.0) / KM_PER_AU;
+        let mut moon = Planetesimal::on_orbit(moon_a, 0.0, random_id(&mut rng));
+        moon.mass = 1.0e-3;
+        ring.open_gaps(&1.0e-3, &[moon], &mut rng);
+        assert!(ring.ringlets.is_empty());
+    }
+
+    #[test]
+    fn saturn_optical_depth() {
+        // Saturn's B ring, ~100 g/cm2 of water ice
+        let tau = optical_depth(&100.0, &0.9);
+        assert!(tau > 1.0 && tau < 3.0);
     }
 }
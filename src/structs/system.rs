@@ -1,9 +1,14 @@
+use crate::consts::*;
 use crate::enviro::*;
 #[cfg(events_log)]
 use crate::events_log::accrete_event::AccreteEvents;
 #[cfg(events_log)]
 use crate::events_log::event_source::EventSource;
+use crate::lagrange::trojan_swarms;
+use crate::solar_system::sol_planets;
+use crate::structs::circumplanetary::{irregular_orbit, regular_moons};
 use crate::structs::*;
+use crate::structure::StructureModel;
 use crate::utils::*;
 
 use rand::distributions::WeightedIndex;
@@ -24,6 +29,13 @@ pub struct System {
     pub outer_dust: f64,
     pub dust_bands: Vec<DustBand>,
     pub dust_left: bool,
+    pub disk: Disk,
+    // water ice condensation distance, AU
+    pub snow_line: f64,
+    pub asteroid_belts: Vec<AsteroidBelt>,
+    // units of solar masses, bodies scattered away by protected pinned planets during accretion, they join the cometary reservoir
+    pub scattered_mass: f64,
+    pub cometary_reservoir: CometaryReservoir,
 }
 
 impl System {
@@ -33,14 +45,16 @@ impl System {
         k: f64,
         cloud_eccentricity: f64,
         b: f64,
+        disk: Disk,
+        system_age: f64,
     ) -> Self {
-        let primary_star = PrimaryStar::new(stellar_mass);
-        let planetesimal_inner_bound = innermost_planet(&stellar_mass);
-        let planetesimal_outer_bound = outermost_planet(&stellar_mass);
-        let inner_dust = 0.0;
-        let outer_dust = stellar_dust_limit(&stellar_mass);
-        let dust_band = DustBand::new(outer_dust, inner_dust, true, true);
-        let dust_bands = vec![dust_band];
+        let primary_star = PrimaryStar::new(stellar_mass, system_age);
+        let inner_dust = disk.inner_edge(&stellar_mass);
+        let outer_dust = disk.outer_edge(&stellar_mass);
+        let planetesimal_inner_bound = innermost_planet(&stellar_mass).max(inner_dust);
+        let planetesimal_outer_bound = outermost_planet(&stellar_mass).min(outer_dust);
+        let dust_bands = disk.dust_bands(&stellar_mass);
+        let snow_line = disk.snow_line(&primary_star.stellar_luminosity);
 
         Self {
             primary_star,
@@ -55,9 +69,79 @@ impl System {
             outer_dust,
             dust_bands,
             dust_left: true,
+            disk,
+            snow_line,
+            asteroid_belts: Vec::new(),
+            scattered_mass: 0.0,
+            cometary_reservoir: CometaryReservoir::default(),
         }
     }
 
+    /// The Sun with its eight planets and their major moons at measured masses and orbits, at the present age of the Sun.
+    /// Dust is cleared and environments are left to be derived with process_planets.
+    pub fn sol() -> Self {
+        let mut system = Self::set_initial_conditions(
+            1.0,
+            DUST_DENSITY_COEFF,
+            K,
+            ECCENTRICITY_COEFF,
+            B,
+            Disk::default(),
+            SOLAR_AGE,
+        );
+        system.planets = sol_planets(&system.primary_star);
+        system.dust_bands.clear();
+        system.dust_left = false;
+        system
+    }
+
+    /// Pinned planets take their orbits before accretion with their dust lanes already swept. Invalid pins are skipped.
+    pub fn seed_pinned_planets(
+        &mut self,
+        pinned_planets: &[PinnedPlanet],
+        rng: &mut dyn RngCore,
+        #[cfg(events_log)] events_log: &mut AccreteEvents,
+    ) {
+        let Self {
+            primary_star,
+            planets,
+            b,
+            cloud_eccentricity,
+            planetesimal_inner_bound,
+            planetesimal_outer_bound,
+            dust_bands,
+            dust_left,
+            snow_line,
+            ..
+        } = self;
+
+        for pinned in pinned_planets
+            .iter()
+            .filter(|pinned| pinned.validate().is_ok())
+        {
+            let p = pinned.planetesimal(primary_star, b, snow_line, rng);
+            #[cfg(events_log)]
+            p.event("planetesimal_created", events_log);
+
+            let crit_mass = critical_limit(b, &p.a, &p.e, &primary_star.stellar_luminosity);
+            let min = inner_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+            let max = outer_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
+            update_dust_lanes(dust_bands, min, max, &p.mass, &crit_mass);
+            compress_dust_lanes(dust_bands);
+
+            #[cfg(events_log)]
+            dust_bands.event("dust_bands_updated", events_log);
+
+            planets.push(p);
+        }
+        planets.sort_by(|p1, p2| p1.a.total_cmp(&p2.a));
+        *dust_left = dust_availible(
+            dust_bands,
+            planetesimal_inner_bound,
+            planetesimal_outer_bound,
+        );
+    }
+
     pub fn distribute_planetary_masses(
         &mut self,
         rng: &mut dyn RngCore,
@@ -75,6 +159,9 @@ impl System {
             planetesimal_outer_bound,
             dust_bands,
             dust_left,
+            disk,
+            snow_line,
+            scattered_mass,
             ..
         } = self;
         let PrimaryStar {
@@ -90,12 +177,13 @@ impl System {
 
             let inside_range = inner_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
             let outside_range = outer_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
-            let dust_density = dust_density(dust_density_coeff, stellar_mass, &p.a);
+            let dust_density = disk.dust_density(dust_density_coeff, stellar_mass, &p.a);
             let crit_mass = critical_limit(b, &p.a, &p.e, stellar_luminosity);
 
             if dust_availible(dust_bands, &inside_range, &outside_range) {
                 accrete_dust(
                     &mut p.mass,
+                    &mut p.composition,
                     &p.a,
                     &p.e,
                     &crit_mass,
@@ -103,6 +191,8 @@ impl System {
                     cloud_eccentricity,
                     &dust_density,
                     k,
+                    snow_line,
+                    disk,
                 );
 
                 let min = inner_swept_limit(&p.a, &p.e, &p.mass, cloud_eccentricity);
@@ -132,7 +222,14 @@ impl System {
 
                 planets.push(p);
                 planets.sort_by(|p1, p2| p1.a.partial_cmp(&p2.a).unwrap());
-                coalesce_planetesimals(stellar_luminosity, stellar_mass, planets, rng, #[cfg(events_log)] events_log);
+                *scattered_mass += coalesce_planetesimals(
+                    stellar_luminosity,
+                    stellar_mass,
+                    planets,
+                    rng,
+                    #[cfg(events_log)]
+                    events_log,
+                );
             }
 
             *dust_left = dust_availible(
@@ -143,6 +240,31 @@ impl System {
         }
     }
 
+    /// Icy bodies left beyond and scattered by giant planets after accretion, joined by bodies scattered by protected pinned planets.
+    pub fn form_cometary_reservoir(&mut self, rng: &mut dyn RngCore) {
+        let System {
+            primary_star,
+            planets,
+            disk,
+            dust_density_coeff,
+            snow_line,
+            scattered_mass,
+            cometary_reservoir,
+            ..
+        } = self;
+
+        *cometary_reservoir = CometaryReservoir::new(
+            planets,
+            disk,
+            dust_density_coeff,
+            &primary_star.stellar_mass,
+            snow_line,
+            scattered_mass,
+            rng,
+        );
+    }
+
+    /// Part of the scattered disk that reaches the inner system bombs planets, split by intensity into impactors of equal mass.
     pub fn post_accretion(
         &mut self,
         intensity: u32,
@@ -156,21 +278,33 @@ impl System {
         let Self {
             primary_star,
             planets,
+            cometary_reservoir,
             ..
         } = self;
 
         let mut weights = Vec::new();
         for p in planets.iter_mut() {
-            weights.push(p.mass * p.a);
+            // Protected pinned planets are kept as given
+            weights.push(match p.is_protected {
+                true => 0.0,
+                false => p.mass * p.a,
+            });
         }
 
-        let dist = WeightedIndex::new(&weights).unwrap();
+        let Ok(dist) = WeightedIndex::new(&weights) else {
+            return;
+        };
+        let impactor_mass = cometary_reservoir.impact_mass / intensity as f64;
         for _i in 0..intensity {
             let p = &mut planets[dist.sample(rng)];
             let Planetesimal { a, e, mass, .. } = p;
             let r_inner = inner_effect_limit(a, e, mass);
             let r_outer = outer_effect_limit(a, e, mass);
-            let mut outer_body = Planetesimal::random_outer_body(&r_inner, &r_outer, rng);
+            let Some(mut outer_body) =
+                cometary_reservoir.scatter_impactor(&impactor_mass, &r_inner, &r_outer, rng)
+            else {
+                break;
+            };
 
             #[cfg(events_log)]
             outer_body.event("outer_body_injected", events_log);
@@ -187,17 +321,101 @@ impl System {
         }
     }
 
-    pub fn process_planets(&mut self, rng: &mut dyn RngCore) {
+    /// Giant planets grow regular moon systems in their circumplanetary disks. Captured moons are kept as irregular population.
+    pub fn form_regular_moons(&mut self, rng: &mut dyn RngCore) {
         let System {
             primary_star,
             planets,
+            snow_line,
+            cloud_eccentricity,
             ..
         } = self;
         let PrimaryStar {
             stellar_luminosity,
             stellar_mass,
-            main_seq_age,
+            ..
+        } = primary_star;
+
+        for planet in planets.iter_mut().filter(|p| p.is_gas_giant) {
+            let mut regular_moons = regular_moons(
+                planet,
+                stellar_luminosity,
+                stellar_mass,
+                snow_line,
+                cloud_eccentricity,
+                rng,
+            );
+            planet.moons.append(&mut regular_moons);
+            planet.moons.sort_by(|p1, p2| p1.a.total_cmp(&p2.a));
+        }
+    }
+
+    /// Dust left in the cloud and orbits not cleared by dwarf planets hold belts of small bodies.
+    pub fn form_asteroid_belts(&mut self) {
+        let System {
+            primary_star,
+            planets,
+            dust_bands,
+            disk,
+            dust_density_coeff,
+            snow_line,
+            asteroid_belts,
+            ..
+        } = self;
+        let PrimaryStar {
+            stellar_luminosity,
+            stellar_mass,
+            ..
+        } = primary_star;
+
+        let mut belts = dust_belts(
+            dust_bands,
+            disk,
+            dust_density_coeff,
+            stellar_mass,
+            stellar_luminosity,
+            snow_line,
+        );
+        belts.extend(
+            planets
+                .iter()
+                .filter(|p| p.is_dwarf_planet)
+                .map(dwarf_planet_belt),
+        );
+        *asteroid_belts = merge_belts(belts);
+    }
+
+    /// Massive planets hold Trojan swarms at their L4 and L5 points.
+    pub fn form_trojan_swarms(&mut self, rng: &mut dyn RngCore) {
+        let System {
+            primary_star,
+            planets,
+            ..
+        } = self;
+
+        for planet in planets.iter_mut() {
+            planet.trojans = trojan_swarms(planet, &primary_star.stellar_mass, rng);
+        }
+    }
+
+    pub fn process_planets(
+        &mut self,
+        hydrodynamic_escape: &bool,
+        structure: &dyn StructureModel,
+        rng: &mut dyn RngCore,
+    ) {
+        let System {
+            primary_star,
+            planets,
+            ..
+        } = self;
+        let PrimaryStar {
+            stellar_luminosity,
+            stellar_mass,
+            age,
             ecosphere,
+            stellar_wind,
+            activity,
             ..
         } = primary_star;
 
@@ -205,24 +423,47 @@ impl System {
             planet.derive_planetary_environment(
                 stellar_luminosity,
                 stellar_mass,
-                main_seq_age,
+                age,
                 ecosphere,
+                stellar_wind,
+                activity,
+                hydrodynamic_escape,
+                structure,
                 rng,
             );
-            for moon in planet.moons.iter_mut() {
-                moon.derive_planetary_environment(
+            let Planetesimal {
+                a: planet_a,
+                e: planet_e,
+                mass: planet_mass,
+                orbital_period_days,
+                seasons,
+                moons,
+                ..
+            } = planet;
+            for moon in moons.iter_mut() {
+                moon.derive_moon_environment(
+                    planet_a,
+                    planet_e,
+                    planet_mass,
+                    orbital_period_days,
+                    seasons,
                     stellar_luminosity,
-                    &planet.mass,
-                    main_seq_age,
+                    stellar_mass,
+                    age,
                     ecosphere,
+                    stellar_wind,
+                    activity,
+                    hydrodynamic_escape,
+                    structure,
                     rng,
                 );
             }
+            sculpt_rings(planet, rng);
         }
     }
 }
 
-/// Check planetesimal coalescence
+/// Check planetesimal coalescence, returns mass (solar masses) of bodies scattered away by protected pinned planets
 pub fn coalesce_planetesimals(
     primary_star_luminosity: &f64,
     primary_star_mass: &f64,
@@ -230,28 +471,55 @@ pub fn coalesce_planetesimals(
     rng: &mut dyn RngCore,
     #[cfg(events_log)]
     events_log: &mut AccreteEvents,
-) {
+) -> f64 {
+    let mut scattered_mass = 0.0;
     let mut next_planets = Vec::new();
     for (i, p) in planets.iter_mut().enumerate() {
         if i == 0 {
             next_planets.push(p.clone());
         } else if let Some(prev_p) = next_planets.last_mut() {
             if check_orbits_intersect(p.a, p.e, p.mass, prev_p.a, prev_p.e, prev_p.mass) {
-                planetesimals_intersect(
-                    p,
-                    prev_p,
-                    primary_star_luminosity,
-                    primary_star_mass,
-                    rng,
-                    #[cfg(events_log)]
+                // Two pinned planets are left as given, bodies crossing protected pinned planet are scattered out of the system
+                match (
+                    prev_p.is_pinned && p.is_pinned,
+                    prev_p.is_protected,
+                    p.is_protected,
+                ) {
+                    (true, _, _) => next_planets.push(p.clone()),
+                    (false, true, _) => {
+                        #[cfg(events_log)]
+                        p.event(
+                            format!("planetesimal_scattered:{}", prev_p.id).as_str(),
+                            events_log,
+                        );
+                        scattered_mass += p.mass;
+                    }
+                    (false, false, true) => {
+                        #[cfg(events_log)]
+                        prev_p.event(
+                            format!("planetesimal_scattered:{}", p.id).as_str(),
+                            events_log,
+                        );
+                        scattered_mass += prev_p.mass;
+                        *prev_p = p.clone();
+                    }
+                    (false, false, false) => planetesimals_intersect(
+                        p,
+                        prev_p,
+                        primary_star_luminosity,
+                        primary_star_mass,
+                        rng,
+                        #[cfg(events_log)]
                         events_log,
-                );
+                    ),
+                }
             } else {
                 next_planets.push(p.clone());
             }
         }
     }
     *planets = next_planets;
+    scattered_mass
 }
 
 /// Two planetesimals intersect
@@ -276,7 +544,21 @@ pub fn planetesimals_intersect(
         let roche_limit = roche_limit_au(&larger.mass, &smaller.mass, &smaller.radius);
         // Planetesimals collide or one capture another as moon
         if (prev_p.a - p.a).abs() <= roche_limit * 2.0 {
-            *prev_p = coalesce_two_planets(prev_p, p, #[cfg(events_log)]  events_log);
+            // Pinned planet that can grow keeps its identity while it is the larger body, smaller one is absorbed
+            *prev_p = match larger.is_pinned {
+                true => coalesce_two_planets(
+                    &larger,
+                    &smaller,
+                    #[cfg(events_log)]
+                    events_log,
+                ),
+                false => coalesce_two_planets(
+                    prev_p,
+                    p,
+                    #[cfg(events_log)]
+                    events_log,
+                ),
+            };
         } else {
             *prev_p = capture_moon(&larger, &smaller, primary_star_mass, rng, #[cfg(events_log)] events_log);
             prev_p
@@ -334,6 +616,7 @@ fn coalesce_two_planets(
     let new_eccn = term4.abs().sqrt();
     let mut coalesced = a.clone();
 
+    coalesced.composition = a.composition.mix(&a.mass, &b.composition, &b.mass);
     coalesced.mass = float_to_precision(new_mass);
     coalesced.a = new_axis;
     coalesced.e = new_eccn;
@@ -369,6 +652,7 @@ fn capture_moon(
     let mut planet = larger.clone();
     let mut moon = smaller.clone();
     moon.is_moon = true;
+    #[cfg(events_log)]
     let moon_id = moon.id.clone();
 
     // Recalcualte planetary axis
@@ -385,13 +669,14 @@ fn capture_moon(
     planet.distance_to_primary_star = new_axis;
     planet.hill_sphere = hill_sphere_au(&planet.a, &planet.e, &planet.mass, stellar_mass);
 
+    // Captured body keeps the composition it accreted
     // Add moon to planetary moons, recalculate disturbed orbits of moons
     planet.moons.append(&mut moon.moons);
     planet.moons.push(moon);
 
-    for m in planet.moons.iter_mut() {
-        m.a = rng.gen_range(0.0..planet.hill_sphere);
-        m.e = random_eccentricity(rng);
+    // Regular moons stay in the disk plane, captured ones are scattered on wide irregular orbits
+    for m in planet.moons.iter_mut().filter(|m| !m.is_regular_moon) {
+        (m.a, m.e) = irregular_orbit(&planet.hill_sphere, rng);
         m.b = m.a * (1.0 - m.e.powf(2.0)).sqrt();
         m.distance_to_primary_star = planet.a;
     }
@@ -408,29 +693,27 @@ fn capture_moon(
 fn moons_to_rings(planet: &mut Planetesimal,
                   #[cfg(events_log)] events_log: &mut AccreteEvents) {
     let mut next_moons = Vec::new();
-    for m in planet.moons.iter_mut() {
+    let mut rings = Vec::new();
+    for m in planet.moons.iter() {
         let roche_limit = roche_limit_au(&planet.mass, &m.mass, &m.radius);
         let moon_perhelion = perihelion_distance(&m.a, &m.e);
         if moon_perhelion <= roche_limit * 2.0 {
-            let ring = Ring::from_planet(roche_limit, m);
+            let ring = Ring::from_moon(planet, m, roche_limit);
             #[cfg(events_log)]
             ring.event(
                 format!("moon_to_ring:{}:{}", planet.id, m.id).as_str(),
                 events_log,
             );
-            planet.rings.push(ring);
+            rings.push(ring);
         } else {
             next_moons.push(m.clone());
         }
     }
 
+    planet.rings.append(&mut rings);
     planet.moons = next_moons;
 }
 
-fn stellar_dust_limit(stellar_mass_ratio: &f64) -> f64 {
-    200.0 * stellar_mass_ratio.powf(1.0 / 3.0)
-}
-
 /// "...the semimajor axes of planetary nuclei can never be greater than 50 distance units, which effectively sets an outer boundary to the problem. An inner boundary was also established, arbitrarily at 0.3 distance unit. (More than 92 percent of the total cloud mass lies between these bounds.)"
 fn outermost_planet(stellar_mass_ratio: &f64) -> f64 {
     50.0 * stellar_mass_ratio.powf(0.33)
@@ -0,0 +1,191 @@
+use crate::consts::*;
+use crate::enviro::*;
+use crate::structs::{Composition, Planetesimal};
+use crate::utils::*;
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Mass-radius relation used to derive planetary radius and density.
+pub trait StructureModel {
+    /// Returns equatorial radius in km and density in g/cc.
+    fn radius_and_density(
+        &self,
+        planet: &Planetesimal,
+        ecosphere_radius: &f64,
+        rng: &mut dyn RngCore,
+    ) -> (f64, f64);
+}
+
+/// Original Starform approach: Kothari radius for solid planets, empirical density for gas giants.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KothariStructure;
+
+impl StructureModel for KothariStructure {
+    fn radius_and_density(
+        &self,
+        planet: &Planetesimal,
+        ecosphere_radius: &f64,
+        _rng: &mut dyn RngCore,
+    ) -> (f64, f64) {
+        let Planetesimal {
+            mass,
+            is_gas_giant,
+            orbit_zone,
+            distance_to_primary_star,
+            ..
+        } = planet;
+        if *is_gas_giant {
+            let density = empirical_density(
+                mass,
+                distance_to_primary_star,
+                ecosphere_radius,
+                is_gas_giant,
+            );
+            return (volume_radius(mass, &density), density);
+        }
+        let radius = kothari_radius(mass, is_gas_giant, orbit_zone);
+        (radius, volume_density(mass, &radius))
+    }
+}
+
+/// Probabilistic power law fitted to known planets and small stars.
+/// [Chen & Kipping 2017, Probabilistic Forecasting of the Masses and Radii of Other Worlds](https://arxiv.org/abs/1603.08614)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChenKippingStructure;
+
+impl StructureModel for ChenKippingStructure {
+    fn radius_and_density(
+        &self,
+        planet: &Planetesimal,
+        _ecosphere_radius: &f64,
+        rng: &mut dyn RngCore,
+    ) -> (f64, f64) {
+        let earth_masses = planet.mass * EARTH_MASSES_PER_SOLAR_MASS;
+        let (log_radius, scatter) = chen_kipping_log_radius(&earth_masses);
+        // Nothing can be denser than pure iron of the same mass
+        let iron_radius = 0.86 * earth_masses.powf(1.0 / 3.7);
+        let earth_radii = 10.0_f64
+            .powf(gaussian(log_radius, scatter, rng))
+            .max(iron_radius);
+        let radius = float_to_precision(earth_radii * EARTH_RADIUS_IN_KM);
+        (radius, volume_density(&planet.mass, &radius))
+    }
+}
+
+/// Interior model driven by accreted composition: iron core fraction sets rocky radius, ice inflates it and gas envelope puffs up sub-Neptunes.
+/// [Zeng et al. 2016, Mass-Radius Relation for Rocky Planets](https://arxiv.org/abs/1512.08827)
+/// [Lopez & Fortney 2014, Understanding the Mass-Radius Relation for Sub-Neptunes](https://arxiv.org/abs/1311.0329)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompositionStructure;
+
+impl StructureModel for CompositionStructure {
+    fn radius_and_density(
+        &self,
+        planet: &Planetesimal,
+        _ecosphere_radius: &f64,
+        _rng: &mut dyn RngCore,
+    ) -> (f64, f64) {
+        let earth_masses = planet.mass * EARTH_MASSES_PER_SOLAR_MASS;
+        let earth_radii = composition_radius(&earth_masses, &planet.composition);
+        let radius = float_to_precision(earth_radii * EARTH_RADIUS_IN_KM);
+        (radius, volume_density(&planet.mass, &radius))
+    }
+}
+
+/// Structure models available from configuration.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Structure {
+    Kothari(KothariStructure),
+    ChenKipping(ChenKippingStructure),
+    Composition(CompositionStructure),
+}
+
+impl Default for Structure {
+    fn default() -> Self {
+        Structure::Kothari(KothariStructure)
+    }
+}
+
+impl StructureModel for Structure {
+    fn radius_and_density(
+        &self,
+        planet: &Planetesimal,
+        ecosphere_radius: &f64,
+        rng: &mut dyn RngCore,
+    ) -> (f64, f64) {
+        match self {
+            Structure::Kothari(model) => model.radius_and_density(planet, ecosphere_radius, rng),
+            Structure::ChenKipping(model) => {
+                model.radius_and_density(planet, ecosphere_radius, rng)
+            }
+            Structure::Composition(model) => {
+                model.radius_and_density(planet, ecosphere_radius, rng)
+            }
+        }
+    }
+}
+
+/// Mean log10 of radius in Earth radii and its intrinsic scatter for mass in Earth masses.
+/// Terran, Neptunian, Jovian and stellar regimes are continuous power laws.
+pub fn chen_kipping_log_radius(earth_masses: &f64) -> (f64, f64) {
+    let log_mass = earth_masses.log10();
+    let terran = (0.00346, 0.2790, 0.0403);
+    let neptunian = (-0.0925, 0.589, 0.146);
+    let jovian = (1.25, -0.044, 0.0737);
+    let stellar = (-2.85, 0.881, 0.0352);
+    let (constant, slope, scatter) = match *earth_masses {
+        m if m < 2.04 => terran,
+        m if m < 131.6 => neptunian,
+        m if m < 26600.0 => jovian,
+        _ => stellar,
+    };
+    (constant + slope * log_mass, scatter)
+}
+
+/// Radius in Earth radii of a body with given mass in Earth masses and bulk composition.
+pub fn composition_radius(earth_masses: &f64, composition: &Composition) -> f64 {
+    let solids = composition.solids();
+    let core_masses = earth_masses * solids;
+    let ice_in_solids = match solids > 0.0 {
+        true => composition.ice / solids,
+        false => 0.0,
+    };
+    let rocky_radius =
+        (1.07 - 0.21 * composition.core_mass_fraction()) * core_masses.powf(1.0 / 3.7);
+    let core_radius = rocky_radius * (1.0 + 0.5 * ice_in_solids);
+
+    if composition.gas <= 0.0 {
+        return core_radius;
+    }
+    let envelope = 2.06 * earth_masses.powf(-0.21) * (composition.gas / 0.05).powf(0.59);
+    let radius = core_radius + envelope;
+    // Envelope dominated giants follow the empirical Jovian branch
+    match composition.gas >= 0.5 {
+        true => radius.max(10.0_f64.powf(chen_kipping_log_radius(earth_masses).0)),
+        false => radius,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chen_kipping_is_continuous() {
+        for m in [2.04, 131.6, 26600.0] {
+            let below = chen_kipping_log_radius(&(m * 0.9999)).0;
+            let above = chen_kipping_log_radius(&(m * 1.0001)).0;
+            assert!((below - above).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn earth_composition_radius() {
+        let earth = Composition::default();
+        let radius = composition_radius(&1.0, &earth);
+        assert!(radius > 0.95 && radius < 1.05);
+        let water_world = Composition::icy();
+        assert!(composition_radius(&1.0, &water_world) > radius);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::consts::*;
+use crate::enviro::acceleration;
+use crate::structs::Planetesimal;
+use crate::utils::*;
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Rigidity of rock, dyne/cm2.
+/// [Gladman et al. 1996, Synchronous locking of tidally evolving satellites](https://doi.org/10.1006/icar.1996.0117)
+const ROCK_RIGIDITY: f64 = 3.0e11;
+
+/// Rigidity of water ice, dyne/cm2.
+const ICE_RIGIDITY: f64 = 4.0e10;
+
+/// Tidal dissipation factor of rocky and icy bodies.
+const SOLID_Q: f64 = 100.0;
+
+/// Tidal dissipation factor of gas giants, Jupiter's Q is about 1e5.
+const GAS_GIANT_Q: f64 = 1.0e5;
+
+/// Normalized moment of inertia C / (m R^2) of rocky and gas giant bodies.
+const SOLID_MOMENT_OF_INERTIA: f64 = 0.33;
+const GAS_GIANT_MOMENT_OF_INERTIA: f64 = 0.254;
+
+/// Permanent equatorial asymmetry (B - A) / C of solid bodies, as Mercury and the Moon. Fluid bodies have none and pass through resonances.
+const SOLID_ASYMMETRY: f64 = 1.0e-4;
+
+/// Velocity dependence of tidal torque near resonance, gives Mercury about 7% chance of capture into 3:2.
+/// [Goldreich & Peale 1966, Spin-orbit coupling in the solar system](https://doi.org/10.1086/109947)
+const TORQUE_VELOCITY_FACTOR: f64 = 3.5;
+
+/// Spin-orbit resonances crossed by despinning body before synchronous rotation, rotations per orbit as 2:1.
+const RESONANCES: [(u32, u32); 3] = [(5, 2), (2, 1), (3, 2)];
+
+/// Spin history of a body braked by tides raised by its host, star for planets and planet for moons.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TidalEvolution {
+    // primordial rotation period, hours
+    pub initial_day_hours: f64,
+    // tidal Love number k2
+    pub love_number: f64,
+    pub quality_factor: f64,
+    // time to despin from primordial to synchronous rotation, years
+    pub locking_timescale: f64,
+    // rotations per orbit, 1:1 for synchronous rotation, none for freely spinning body
+    pub resonance: Option<(u32, u32)>,
+    // probability of capture into the resonance body settled in
+    pub capture_probability: f64,
+    // sidereal rotation period at system age, hours
+    pub day_hours: f64,
+}
+
+impl TidalEvolution {
+    /// Despins a body with derived radius, density, composition and orbital period from primordial rotation over system age in years, host mass in solar masses.
+    /// Constant phase lag torque brakes rotation at constant rate. Solid bodies on eccentric orbits may be captured into resonances on the way with Goldreich & Peale probability, synchronous rotation captures always.
+    pub fn new(planet: &Planetesimal, host_mass: &f64, age: &f64, rng: &mut dyn RngCore) -> Self {
+        let Planetesimal {
+            a,
+            e,
+            mass,
+            radius,
+            density,
+            orbital_period_days,
+            composition,
+            is_gas_giant,
+            ..
+        } = planet;
+        let age = age.min(UNIVERSE_AGE);
+        let mass_in_grams = mass * SOLAR_MASS_IN_GRAMS;
+        let radius_in_cm = radius * CM_PER_KM;
+        let orbital_period_hours = orbital_period_days * 24.0;
+        if mass_in_grams <= 0.0 || radius_in_cm <= 0.0 || orbital_period_hours <= 0.0 {
+            return Self::default();
+        }
+        let (moment_of_inertia, quality_factor, asymmetry) = match is_gas_giant {
+            true => (GAS_GIANT_MOMENT_OF_INERTIA, GAS_GIANT_Q, 0.0),
+            false => (SOLID_MOMENT_OF_INERTIA, SOLID_Q, SOLID_ASYMMETRY),
+        };
+        let rigidity = match is_gas_giant {
+            true => 0.0,
+            false => composition.ice * ICE_RIGIDITY + (1.0 - composition.ice) * ROCK_RIGIDITY,
+        };
+        let love_number = love_number(
+            &rigidity,
+            density,
+            &acceleration(mass, radius),
+            &radius_in_cm,
+        );
+
+        // Dole's primordial rotation, angular momentum grows with mass
+        let initial_spin =
+            (2.0 * J * mass_in_grams / (moment_of_inertia * radius_in_cm.powf(2.0))).sqrt();
+        let mean_motion = RADIANS_PER_ROTATION / (orbital_period_hours * SECONDS_PER_HOUR);
+        let despin_rate = 3.0
+            * love_number
+            * GRAV_CONSTANT
+            * (host_mass * SOLAR_MASS_IN_GRAMS).powf(2.0)
+            * radius_in_cm.powf(3.0)
+            / (moment_of_inertia * mass_in_grams * quality_factor * (a * CM_PER_AU).powf(6.0));
+        let despin_rate_per_year = despin_rate * SECONDS_PER_YEAR;
+        let locking_timescale = (initial_spin - mean_motion).abs() / despin_rate_per_year;
+
+        let mut resonance = None;
+        let mut capture_probability = 0.0;
+        if initial_spin > mean_motion {
+            for (rotations, orbits) in RESONANCES.iter() {
+                let ratio = *rotations as f64 / *orbits as f64;
+                if initial_spin <= ratio * mean_motion {
+                    continue;
+                }
+                if (initial_spin - ratio * mean_motion) / despin_rate_per_year > age {
+                    break;
+                }
+                let probability = capture_probability_at(&(2 * rotations / orbits), e, &asymmetry);
+                if rng.gen_range(0.0..1.0) < probability {
+                    resonance = Some((*rotations, *orbits));
+                    capture_probability = probability;
+                    break;
+                }
+            }
+        }
+        if resonance.is_none() && locking_timescale <= age {
+            resonance = Some((1, 1));
+            capture_probability = 1.0;
+        }
+
+        let day_hours = match resonance {
+            Some((rotations, orbits)) => orbital_period_hours * orbits as f64 / rotations as f64,
+            None => {
+                let spin = initial_spin
+                    - (initial_spin - mean_motion).signum() * despin_rate_per_year * age;
+                RADIANS_PER_ROTATION / (SECONDS_PER_HOUR * spin)
+            }
+        };
+
+        Self {
+            initial_day_hours: float_to_precision(
+                RADIANS_PER_ROTATION / (SECONDS_PER_HOUR * initial_spin),
+            ),
+            love_number: float_to_precision(love_number),
+            quality_factor,
+            locking_timescale: float_to_precision(locking_timescale),
+            resonance,
+            capture_probability: float_to_precision(capture_probability),
+            day_hours: float_to_precision(day_hours),
+        }
+    }
+}
+
+/// Love number k2 of homogeneous elastic body, rigidity in dyne/cm2, density in g/cc, surface acceleration in cm/sec2 and radius in cm. Fluid body has k2 = 1.5.
+pub fn love_number(rigidity: &f64, density: &f64, surface_accel: &f64, radius: &f64) -> f64 {
+    1.5 / (1.0 + 19.0 * rigidity / (2.0 * density * surface_accel * radius))
+}
+
+/// Eccentricity function H(p, e) of spin-orbit resonance with p / 2 rotations per orbit, series from Goldreich & Peale 1966.
+pub fn resonance_strength(p: &u32, e: &f64) -> f64 {
+    match p {
+        2 => 1.0 - 5.0 / 2.0 * e.powf(2.0) + 13.0 / 16.0 * e.powf(4.0),
+        3 => 7.0 / 2.0 * e - 123.0 / 16.0 * e.powf(3.0),
+        4 => 17.0 / 2.0 * e.powf(2.0) - 115.0 / 6.0 * e.powf(4.0),
+        5 => 845.0 / 48.0 * e.powf(3.0) - 32525.0 / 768.0 * e.powf(5.0),
+        _ => 0.0,
+    }
+}
+
+/// Probability of capture into resonance with p / 2 rotations per orbit for body with permanent asymmetry (B - A) / C, 2 / (1 + π / 2V * sqrt(C / 3(B - A)H)).
+pub fn capture_probability_at(p: &u32, e: &f64, asymmetry: &f64) -> f64 {
+    let libration = (3.0 * asymmetry * resonance_strength(p, e)).max(0.0).sqrt();
+    if libration == 0.0 {
+        return 0.0;
+    }
+    (2.0 / (1.0 + PI / (2.0 * TORQUE_VELOCITY_FACTOR * libration))).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::Composition;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn mercury_capture_probability() {
+        let probability = capture_probability_at(&3, &0.206, &SOLID_ASYMMETRY);
+        assert!(probability > 0.05 && probability < 0.1);
+        assert_eq!(capture_probability_at(&3, &0.0, &SOLID_ASYMMETRY), 0.0);
+        assert_eq!(capture_probability_at(&3, &0.206, &0.0), 0.0);
+    }
+
+    #[test]
+    fn mercury_resonance_is_not_locking() {
+        let mut mercury = Planetesimal::on_orbit(0.387, 0.206, "mercury".to_string());
+        mercury.mass = 1.66e-7;
+        mercury.radius = 2440.0;
+        mercury.density = 5.43;
+        mercury.orbital_period_days = 88.0;
+        mercury.composition = Composition::condensate(&0.387, &2.7);
+        let captured = (0..1000).find_map(|seed| {
+            let mut planet = mercury.clone();
+            planet.despin(&1.0, &4.57e9, &mut ChaCha8Rng::seed_from_u64(seed));
+            (planet.tides.resonance == Some((3, 2))).then_some(planet)
+        });
+        let planet = captured.expect("no capture into 3:2 resonance");
+        assert!(!planet.is_tidally_locked && planet.resonant_period);
+        assert!((planet.day_hours - 88.0 * 24.0 * 2.0 / 3.0).abs() < 1.0e-6);
+    }
+}
@@ -26,6 +26,13 @@ pub fn about(value: f64, variation: f64, rng: &mut dyn RngCore) -> f64 {
     rng.gen_range(value - variation..value + variation)
 }
 
+/// Normally distributed value (Box-Muller transform).
+pub fn gaussian(mean: f64, std_dev: f64, rng: &mut dyn RngCore) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
 pub fn reduced_mass(mass: &f64) -> f64 {
     (mass / (1.0 + mass)).powf(1.0 / 4.0)
 }
@@ -118,6 +125,81 @@ pub fn semi_minor_axis(a: f64, e: f64) -> f64 {
     float_to_precision(b)
 }
 
+/// Eccentric anomaly for mean anomaly in radians, Kepler's equation solved by Newton's method.
+pub fn eccentric_anomaly(mean_anomaly: &f64, e: &f64) -> f64 {
+    let mut anomaly = match *e > 0.8 {
+        true => PI,
+        false => *mean_anomaly,
+    };
+    for _ in 0..50 {
+        let delta = (anomaly - e * anomaly.sin() - mean_anomaly) / (1.0 - e * anomaly.cos());
+        anomaly -= delta;
+        if delta.abs() < 1.0e-12 {
+            break;
+        }
+    }
+    anomaly
+}
+
+/// True anomaly in radians from eccentric anomaly.
+pub fn true_anomaly(eccentric_anomaly: &f64, e: &f64) -> f64 {
+    2.0 * ((1.0 + e).sqrt() * (eccentric_anomaly / 2.0).sin())
+        .atan2((1.0 - e).sqrt() * (eccentric_anomaly / 2.0).cos())
+}
+
+/// Position (x, y) in AU relative to host body at time in days from epoch, for orbit in the common plane of the system.
+/// Direction of periapsis and mean anomaly at epoch are in degrees.
+pub fn position_on_orbit(
+    a: &f64,
+    e: &f64,
+    orbital_period_days: &f64,
+    longitude_of_periapsis: &f64,
+    mean_anomaly: &f64,
+    time: &f64,
+) -> (f64, f64) {
+    let phase = match *orbital_period_days > 0.0 {
+        true => 2.0 * PI * time / orbital_period_days,
+        false => 0.0,
+    };
+    let mean_anomaly = (mean_anomaly.to_radians() + phase).rem_euclid(2.0 * PI);
+    let eccentric_anomaly = eccentric_anomaly(&mean_anomaly, e);
+    let longitude = true_anomaly(&eccentric_anomaly, e) + longitude_of_periapsis.to_radians();
+    let distance = a * (1.0 - e * eccentric_anomaly.cos());
+    (distance * longitude.cos(), distance * longitude.sin())
+}
+
+/// Velocity (x, y) in AU/day relative to host body at time in days from epoch, for orbit of position_on_orbit.
+pub fn velocity_on_orbit(
+    a: &f64,
+    e: &f64,
+    orbital_period_days: &f64,
+    longitude_of_periapsis: &f64,
+    mean_anomaly: &f64,
+    time: &f64,
+) -> (f64, f64) {
+    if *orbital_period_days <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let mean_anomaly =
+        (mean_anomaly.to_radians() + 2.0 * PI * time / orbital_period_days).rem_euclid(2.0 * PI);
+    let eccentric_anomaly = eccentric_anomaly(&mean_anomaly, e);
+    let periapsis = longitude_of_periapsis.to_radians();
+    let longitude = true_anomaly(&eccentric_anomaly, e) + periapsis;
+    let speed = 2.0 * PI * a / (orbital_period_days * (1.0 - e.powf(2.0)).sqrt());
+    (
+        -speed * (longitude.sin() + e * periapsis.sin()),
+        speed * (longitude.cos() + e * periapsis.cos()),
+    )
+}
+
+/// Mean anomaly in radians, between 0 and 2π, reached at given true anomaly.
+pub fn mean_anomaly_at(true_anomaly: &f64, e: &f64) -> f64 {
+    let eccentric_anomaly = 2.0
+        * ((1.0 - e).sqrt() * (true_anomaly / 2.0).sin())
+            .atan2((1.0 + e).sqrt() * (true_anomaly / 2.0).cos());
+    (eccentric_anomaly - e * eccentric_anomaly.sin()).rem_euclid(2.0 * PI)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +212,12 @@ mod tests {
             roche_limit_au(&earth_mass, &moon_mass, &moon_radius) * KM_PER_AU;
         assert!(earth_moon_roche_limit > 9400.0 && earth_moon_roche_limit < 9600.0);
     }
+
+    #[test]
+    fn kepler_roundtrip() {
+        let e = 0.6;
+        let mean_anomaly = 1.2;
+        let anomaly = true_anomaly(&eccentric_anomaly(&mean_anomaly, &e), &e);
+        assert!((mean_anomaly_at(&anomaly, &e) - mean_anomaly).abs() < 1.0e-9);
+    }
 }